@@ -0,0 +1,375 @@
+//! Discovery of the `#[univm_platform::function]`s of a guest crate, which
+//! program bindings are generated for.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::Package;
+use proc_macro2::TokenTree;
+use syn::{
+    Attribute, FnArg, Item, ItemFn, Path as SynPath, Token, Type, Visibility,
+    punctuated::Punctuated,
+};
+
+/// Public guest function, reachable from the library of the guest crate.
+pub struct Function {
+    /// Path of the module defining the function, starting with the crate.
+    pub module: String,
+    pub name: String,
+}
+
+impl Function {
+    /// Program name - the function name in `PascalCase`.
+    pub fn program_name(&self) -> String {
+        self.name
+            .split('_')
+            .map(|part| {
+                let mut chars = part.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    /// Path of a type emitted by the attribute alongside the function.
+    pub fn binding(&self, ty: &str) -> String {
+        format!("::{}::__univm_bindings_{}::{ty}", self.module, self.name)
+    }
+}
+
+/// Guest crate, along with the functions exposed by its library.
+pub struct Guest {
+    /// Package name, as an identifier.
+    pub name: String,
+    pub functions: Vec<Function>,
+
+    /// Names of the functions the binary dispatches to, as listed by its
+    /// `entrypoint!` invocation.
+    pub entrypoint: Vec<String>,
+}
+
+impl Guest {
+    /// Whether the guest commits the selector after the output, which the
+    /// `univm_platform::dispatch` does for binaries exposing several functions.
+    pub fn commits_selector(&self) -> bool {
+        self.entrypoint.len() > 1
+    }
+}
+
+/// Finds the functions exposed by the library of the guest crate, and the
+/// ones its binary dispatches to.
+pub fn discover(package: &Package) -> Guest {
+    let name = package.name.replace('-', "_");
+    let entrypoint = find_entrypoint(package);
+
+    let Some(lib) = package
+        .targets
+        .iter()
+        .find(|target| target.is_lib() || target.is_rlib())
+    else {
+        println!(
+            "cargo:warning=guest crate {} has no library, no programs generated",
+            package.name
+        );
+        return Guest {
+            name,
+            functions: Vec::new(),
+            entrypoint,
+        };
+    };
+
+    let src_path: &Path = lib.src_path.as_ref();
+    let mut functions = Vec::new();
+    scan_file(
+        src_path,
+        src_path.parent().unwrap().to_path_buf(),
+        lib.name.replace('-', "_"),
+        &mut functions,
+    );
+    check_distinct_names(&functions);
+    check_dispatched(&functions, &entrypoint);
+
+    Guest {
+        name,
+        functions,
+        entrypoint,
+    }
+}
+
+/// Finds the `entrypoint!` invocation in the binary of the guest crate.
+fn find_entrypoint(package: &Package) -> Vec<String> {
+    package
+        .targets
+        .iter()
+        .filter(|target| target.is_bin())
+        .find_map(|target| {
+            let content = fs::read_to_string(&target.src_path).unwrap();
+            let file = syn::parse_file(&content)
+                .unwrap_or_else(|err| panic!("cannot parse {}: {err}", target.src_path));
+
+            entrypoint_functions(&file.items)
+        })
+        .unwrap_or_else(|| {
+            panic!(
+                "guest crate {} has no `entrypoint!` invocation",
+                package.name
+            )
+        })
+}
+
+/// Last path segments of the functions listed by the `entrypoint!` macro
+/// invocation among `items`, as the selectors are computed from.
+fn entrypoint_functions(items: &[Item]) -> Option<Vec<String>> {
+    items.iter().find_map(|item| {
+        let Item::Macro(item) = item else {
+            return None;
+        };
+        if item.mac.path.segments.last()?.ident != "entrypoint" {
+            return None;
+        }
+
+        let paths = item
+            .mac
+            .parse_body_with(Punctuated::<SynPath, Token![,]>::parse_terminated)
+            .unwrap_or_else(|err| panic!("cannot parse `entrypoint!` invocation: {err}"));
+
+        Some(
+            paths
+                .iter()
+                .map(|path| path.segments.last().unwrap().ident.to_string())
+                .collect(),
+        )
+    })
+}
+
+/// Programs are only generated for functions the binary dispatches to, as
+/// calling any other one fails at runtime on an unknown selector.
+fn check_dispatched(functions: &[Function], entrypoint: &[String]) {
+    for function in functions {
+        if !entrypoint.contains(&function.name) {
+            panic!(
+                "guest function {}::{} is not listed in `entrypoint!`",
+                function.module, function.name
+            );
+        }
+    }
+}
+
+/// Functions are told apart by name alone - in program names and selectors -
+/// so functions from different modules must not share one.
+fn check_distinct_names(functions: &[Function]) {
+    for (i, function) in functions.iter().enumerate() {
+        if let Some(other) = functions[..i]
+            .iter()
+            .find(|other| other.name == function.name)
+        {
+            panic!(
+                "guest functions {}::{name} and {}::{name} have the same name",
+                other.module,
+                function.module,
+                name = function.name
+            );
+        }
+    }
+}
+
+fn scan_file(path: &Path, dir: PathBuf, module: String, functions: &mut Vec<Function>) {
+    let content = fs::read_to_string(path).unwrap();
+    let file = syn::parse_file(&content)
+        .unwrap_or_else(|err| panic!("cannot parse {}: {err}", path.display()));
+
+    scan_items(&file.items, dir, module, functions);
+}
+
+/// Scans the items of `module`, whose child module files are in `dir`. Only
+/// public modules are descended into, as others are not reachable from the
+/// host.
+fn scan_items(items: &[Item], dir: PathBuf, module: String, functions: &mut Vec<Function>) {
+    for item in items {
+        match item {
+            Item::Fn(item) if is_public(&item.vis) => {
+                if let Some(attr) = item.attrs.iter().find(|attr| is_function(attr)) {
+                    if takes_reference(item) && !has_owned(attr) {
+                        println!(
+                            "cargo:warning=no program generated for {module}::{} - functions \
+                             taking the input by reference need the `owned` input type",
+                            item.sig.ident
+                        );
+                        continue;
+                    }
+
+                    functions.push(Function {
+                        module: module.clone(),
+                        name: item.sig.ident.to_string(),
+                    });
+                }
+            }
+            Item::Mod(item) if is_public(&item.vis) => {
+                let name = item.ident.to_string();
+                let child_dir = dir.join(&name);
+                let child_module = format!("{module}::{name}");
+
+                match &item.content {
+                    Some((_, items)) => scan_items(items, child_dir, child_module, functions),
+                    None => {
+                        let file = dir.join(format!("{name}.rs"));
+                        let file = if file.exists() {
+                            file
+                        } else {
+                            child_dir.join("mod.rs")
+                        };
+
+                        scan_file(&file, child_dir, child_module, functions);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_public(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+/// Whether the attribute is `#[univm_platform::function]`, possibly imported.
+fn is_function(attr: &Attribute) -> bool {
+    let segments = &attr.path().segments;
+
+    match segments.len() {
+        1 => segments[0].ident == "function",
+        2 => segments[0].ident == "univm_platform" && segments[1].ident == "function",
+        _ => false,
+    }
+}
+
+fn takes_reference(item: &ItemFn) -> bool {
+    matches!(
+        item.sig.inputs.first(),
+        Some(FnArg::Typed(arg)) if matches!(*arg.ty, Type::Reference(_))
+    )
+}
+
+/// Whether the attribute has an `owned = Type` argument.
+fn has_owned(attr: &Attribute) -> bool {
+    let Ok(list) = attr.meta.require_list() else {
+        return false;
+    };
+
+    let tokens = list.tokens.clone().into_iter().collect::<Vec<_>>();
+    tokens.windows(2).any(|pair| {
+        matches!(
+            pair,
+            [TokenTree::Ident(name), TokenTree::Punct(punct)]
+                if name == "owned" && punct.as_char() == '='
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn program_name_is_pascal_case() {
+        let function = Function {
+            module: "guest".to_owned(),
+            name: "state_transition".to_owned(),
+        };
+
+        assert_eq!(function.program_name(), "StateTransition");
+        assert_eq!(
+            function.binding("__Input"),
+            "::guest::__univm_bindings_state_transition::__Input"
+        );
+    }
+
+    #[test]
+    fn finds_public_functions() {
+        let file: syn::File = syn::parse_quote! {
+            #[univm_platform::function(SszIo)]
+            pub fn sum(input: Input) -> Output {}
+
+            #[univm_platform::function(RkyvIo)]
+            pub fn view(input: &ArchivedInput) -> Output {}
+
+            #[univm_platform::function(RkyvIo, owned = Input)]
+            pub fn owned_view(input: &ArchivedInput) -> Output {}
+
+            #[function(SszIo)]
+            fn private(input: Input) -> Output {}
+
+            pub mod nested {
+                #[function(SszIo)]
+                pub fn difference(input: Input) -> Output {}
+            }
+        };
+
+        let mut functions = Vec::new();
+        scan_items(
+            &file.items,
+            PathBuf::new(),
+            "guest".to_owned(),
+            &mut functions,
+        );
+
+        let found = functions
+            .iter()
+            .map(|function| format!("{}::{}", function.module, function.name))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                "guest::sum",
+                "guest::owned_view",
+                "guest::nested::difference"
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_entrypoint_functions() {
+        let file: syn::File = syn::parse_quote! {
+            use guest::{sum, nested};
+
+            univm_platform::entrypoint!(sum, nested::difference,);
+        };
+
+        assert_eq!(
+            entrypoint_functions(&file.items).unwrap(),
+            ["sum", "difference"]
+        );
+        assert_eq!(entrypoint_functions(&[]), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "guest function guest::sum is not listed in `entrypoint!`")]
+    fn rejects_functions_not_dispatched() {
+        let function = |name: &str| Function {
+            module: "guest".to_owned(),
+            name: name.to_owned(),
+        };
+
+        check_dispatched(
+            &[function("difference"), function("sum")],
+            &["difference".to_owned()],
+        );
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "guest functions guest::run and guest::nested::run have the same name"
+    )]
+    fn rejects_duplicate_names() {
+        let function = |module: &str| Function {
+            module: module.to_owned(),
+            name: "run".to_owned(),
+        };
+
+        check_distinct_names(&[function("guest"), function("guest::nested")]);
+    }
+}
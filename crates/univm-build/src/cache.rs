@@ -0,0 +1,285 @@
+//! Reuse of guest builds across host builds. Artifacts are keyed by a hash of
+//! everything the guest build depends on - sources of the local packages it
+//! depends on, the lockfile, the generated platform, the compiler id and the
+//! other build inputs reported by the compiler.
+//!
+//! Only the `src/` directories of local packages are collected. Files outside
+//! of them, e.g. read with `include_bytes!` or declared with `#[path]`, are
+//! tracked when added with [`BuildOptions::track`](crate::BuildOptions::track).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use cargo_metadata::{Metadata, Package, PackageId};
+use univm_interface::compiler::Artifact;
+
+/// Files the guest build depends on, along with the directories new sources
+/// may be added to.
+pub struct Sources {
+    pub files: Vec<PathBuf>,
+    pub dirs: Vec<PathBuf>,
+}
+
+impl Sources {
+    /// Collects the manifests, build scripts and sources of the local
+    /// packages `package` depends on (including itself), the lockfile and the
+    /// `tracked` files and directories. Other packages are pinned by the
+    /// lockfile.
+    pub fn collect(metadata: &Metadata, package: &Package, tracked: &[PathBuf]) -> Self {
+        let mut sources = Sources {
+            files: Vec::new(),
+            dirs: Vec::new(),
+        };
+
+        for package in local_dependencies(metadata, &package.id) {
+            let dir: &Path = package.manifest_path.parent().unwrap().as_ref();
+
+            sources.files.push(package.manifest_path.clone().into());
+
+            let build_script = dir.join("build.rs");
+            if build_script.exists() {
+                sources.files.push(build_script);
+            }
+
+            let src = dir.join("src");
+            if src.is_dir() {
+                collect_files(&src, &mut sources.files);
+                sources.dirs.push(src);
+            }
+        }
+
+        for path in tracked.iter() {
+            if path.is_dir() {
+                collect_files(path, &mut sources.files);
+                sources.dirs.push(path.clone());
+            } else {
+                sources.files.push(path.clone());
+            }
+        }
+
+        let lockfile = metadata.workspace_root.join("Cargo.lock");
+        if lockfile.exists() {
+            sources.files.push(lockfile.into());
+        }
+
+        sources.files.sort();
+        sources.files.dedup();
+        sources.dirs.sort();
+        sources.dirs.dedup();
+
+        sources
+    }
+
+    /// Asks cargo to rerun the build script when any of the sources change.
+    pub fn emit_rerun_directives(&self) {
+        for path in self.files.iter().chain(self.dirs.iter()) {
+            println!("cargo:rerun-if-changed={}", path.display());
+        }
+    }
+
+    /// Hashes the sources, together with other inputs of the build.
+    pub fn key(&self, platform: &str, compiler_id: &str, build_inputs: &[String]) -> u64 {
+        let mut hasher = Hasher::new();
+
+        for file in self.files.iter() {
+            hasher.write(file.as_os_str().as_encoded_bytes());
+            hasher.write(&fs::read(file).unwrap());
+        }
+        hasher.write(platform.as_bytes());
+        hasher.write(compiler_id.as_bytes());
+        for input in build_inputs.iter() {
+            hasher.write(input.as_bytes());
+        }
+
+        hasher.0
+    }
+}
+
+/// 64-bit FNV-1a. The hash is stable across Rust versions, unlike
+/// `DefaultHasher`, so that cached artifacts outlive toolchain updates.
+struct Hasher(u64);
+
+impl Hasher {
+    fn new() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+
+    /// Hashes `bytes` prefixed with their length, so that consecutive inputs
+    /// are told apart.
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in (bytes.len() as u64).to_le_bytes().iter().chain(bytes) {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+/// Packages without a source - path dependencies and workspace members -
+/// reachable from `root`.
+fn local_dependencies<'a>(metadata: &'a Metadata, root: &PackageId) -> Vec<&'a Package> {
+    let Some(resolve) = &metadata.resolve else {
+        return metadata.packages.iter().filter(|p| &p.id == root).collect();
+    };
+
+    let mut reachable = vec![root];
+    let mut next = 0;
+    while next < reachable.len() {
+        let id = reachable[next];
+        next += 1;
+
+        let Some(node) = resolve.nodes.iter().find(|node| &node.id == id) else {
+            continue;
+        };
+
+        for dependency in node.dependencies.iter() {
+            if !reachable.contains(&dependency) {
+                reachable.push(dependency);
+            }
+        }
+    }
+
+    metadata
+        .packages
+        .iter()
+        .filter(|package| package.source.is_none() && reachable.contains(&&package.id))
+        .collect()
+}
+
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.is_dir() {
+            collect_files(&path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
+
+/// Artifacts cached in `cache_dir`, one file per guest and compiler - key,
+/// ELF path and program id words, each on its own line.
+pub struct Cache {
+    dir: PathBuf,
+}
+
+impl Cache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, guest: &str, compiler_id: &str) -> PathBuf {
+        self.dir.join(format!("{guest}-{compiler_id}"))
+    }
+
+    /// Artifact built with the same key, if its ELF is still present.
+    pub fn load(&self, guest: &str, compiler_id: &str, key: u64) -> Option<Artifact> {
+        let content = fs::read_to_string(self.path(guest, compiler_id)).ok()?;
+        let mut lines = content.lines();
+
+        if lines.next()? != format!("{key:016x}") {
+            return None;
+        }
+
+        let elf_path = PathBuf::from(lines.next()?);
+        let program_id = lines
+            .next()?
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<_, _>>()
+            .ok()?;
+
+        elf_path.exists().then_some(Artifact {
+            elf_path,
+            program_id,
+        })
+    }
+
+    pub fn store(&self, guest: &str, compiler_id: &str, key: u64, artifact: &Artifact) {
+        fs::create_dir_all(&self.dir).unwrap();
+
+        let program_id = artifact
+            .program_id
+            .iter()
+            .map(u32::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        fs::write(
+            self.path(guest, compiler_id),
+            format!(
+                "{key:016x}\n{}\n{program_id}\n",
+                artifact.elf_path.display()
+            ),
+        )
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reuses_artifact_with_same_key() {
+        let dir = std::env::temp_dir().join(format!("univm-cache-{}", std::process::id()));
+        let cache = Cache::new(dir.clone());
+
+        // The cache file itself stands in for the ELF, which must exist.
+        let artifact = Artifact {
+            elf_path: dir.join("guest-risc0"),
+            program_id: vec![1, 2, u32::MAX],
+        };
+        cache.store("guest", "risc0", 7, &artifact);
+
+        assert_eq!(cache.load("guest", "risc0", 7), Some(artifact));
+        assert_eq!(cache.load("guest", "risc0", 8), None);
+        assert_eq!(cache.load("guest", "sp1", 7), None);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn key_covers_inputs() {
+        let dir = std::env::temp_dir().join(format!("univm-key-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let file = dir.join("main.rs");
+        fs::write(&file, b"fn main() {}").unwrap();
+
+        let sources = Sources {
+            files: vec![file.clone()],
+            dirs: Vec::new(),
+        };
+        let inputs = ["toolchain=rustc 1.88.0".to_owned()];
+        let key = sources.key("platform", "risc0", &inputs);
+        assert_eq!(sources.key("platform", "risc0", &inputs), key);
+        assert_ne!(sources.key("platform", "sp1", &inputs), key);
+        assert_ne!(sources.key("platform", "risc0", &[]), key);
+        assert_ne!(
+            sources.key("platform", "risc0", &["toolchain=rustc 1.89.0".to_owned()]),
+            key
+        );
+
+        fs::write(&file, b"fn main() { }").unwrap();
+        assert_ne!(sources.key("platform", "risc0", &inputs), key);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn hash_is_stable() {
+        // Fixed across builds and toolchains, for cached artifacts to be found.
+        let mut hasher = Hasher::new();
+        hasher.write(b"risc0");
+        assert_eq!(hasher.0, 0x9b94_71ca_caef_e891);
+
+        // Inputs are not merely concatenated.
+        let mut hasher = Hasher::new();
+        hasher.write(b"ris");
+        hasher.write(b"c0");
+        assert_ne!(hasher.0, 0x9b94_71ca_caef_e891);
+    }
+}
@@ -6,11 +6,21 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use cargo_metadata::{Metadata, MetadataCommand, Package};
 use univm_interface::compiler::{CompilationResult, Compiler};
 
+use crate::cache::{Cache, Sources};
+
+mod bindings;
+mod cache;
+mod prebuilt;
+
 pub struct BuildOptions {
     compilers: Vec<Box<dyn Compiler>>,
     crates: Vec<PathBuf>,
+    prebuilt: Option<PathBuf>,
+    export: Option<PathBuf>,
+    tracked: Vec<PathBuf>,
 }
 
 fn get_out_dir() -> PathBuf {
@@ -50,20 +60,38 @@ impl BuildOptions {
         BuildOptions {
             compilers: Vec::new(),
             crates: Vec::new(),
+            prebuilt: None,
+            export: None,
+            tracked: Vec::new(),
         }
     }
 
     pub fn add_crate(mut self, path: impl AsRef<Path>) -> Self {
-        let path = path.as_ref();
+        self.crates.push(resolve_path(path.as_ref()));
+        self
+    }
 
-        if path.is_absolute() {
-            self.crates.push(path.to_path_buf());
-        } else {
-            let manifest_dir: PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
+    /// Loads guests from `dir`, as exported by [`BuildOptions::export`],
+    /// instead of building them. Overridden by the `UNIVM_PREBUILT_DIR`
+    /// environment variable.
+    pub fn prebuilt(mut self, dir: impl AsRef<Path>) -> Self {
+        self.prebuilt = Some(resolve_path(dir.as_ref()));
+        self
+    }
 
-            self.crates.push(manifest_dir.join(path));
-        }
+    /// Exports built guests to `dir`, to be loaded with
+    /// [`BuildOptions::prebuilt`]. Overridden by the `UNIVM_EXPORT_DIR`
+    /// environment variable.
+    pub fn export(mut self, dir: impl AsRef<Path>) -> Self {
+        self.export = Some(resolve_path(dir.as_ref()));
+        self
+    }
 
+    /// Rebuilds guests when `path` changes - a file or directory the guests
+    /// depend on outside the `src/` directories of their local packages, e.g.
+    /// read with `include_bytes!`.
+    pub fn track(mut self, path: impl AsRef<Path>) -> Self {
+        self.tracked.push(resolve_path(path.as_ref()));
         self
     }
 
@@ -72,7 +100,9 @@ impl BuildOptions {
         self
     }
 
-    fn emit_platform(&self, crate_path: &Path) {
+    /// Writes the platform included by the guest, returning its code. The
+    /// file is only rewritten when its code changes, not to rebuild the guest.
+    fn emit_platform(&self, crate_path: &Path) -> String {
         let temp_artifacts_path = crate_path.join(".univm");
         fs::create_dir_all(&temp_artifacts_path).unwrap();
 
@@ -82,64 +112,131 @@ impl BuildOptions {
         let mut cachedir = File::create(temp_artifacts_path.join("CACHEDIR.TAG")).unwrap();
         writeln!(cachedir, "{}", CACHEDIR_TAG_CONTENT).unwrap();
 
-        let mut platform_code = File::create(temp_artifacts_path.join("platform.rs")).unwrap();
-        writeln!(platform_code, "mod __platform_impl {{").unwrap();
-        writeln!(platform_code, "use univm_platform::cfg_zkvm;").unwrap();
+        let mut platform_code = String::new();
+        platform_code.push_str("mod __platform_impl {\n");
+        platform_code.push_str("use univm_platform::cfg_zkvm;\n");
         for vm in self.compilers.iter() {
             let platform = vm.emit_platform().unwrap();
-            write!(platform_code, "{}", platform).unwrap();
+            platform_code.push_str(&platform);
+        }
+        platform_code.push_str("}\n");
+        platform_code.push_str("use __platform_impl::*;\n");
+
+        let platform_path = temp_artifacts_path.join("platform.rs");
+        if fs::read_to_string(&platform_path).ok().as_ref() != Some(&platform_code) {
+            fs::write(platform_path, &platform_code).unwrap();
         }
-        writeln!(platform_code, "}}").unwrap();
-        writeln!(platform_code, "use __platform_impl::*;").unwrap();
+
+        platform_code
     }
 
-    fn build_crate(&self, crate_path: &Path) {
-        self.emit_platform(crate_path);
+    /// Builds the guest crate and writes its programs to
+    /// `$OUT_DIR/univm/<crate>.rs`. Guests are only rebuilt when their sources
+    /// change, otherwise artifacts of the previous build are reused.
+    fn build_crate(&self, crate_path: &Path, metadata: &Metadata, out_dir: &Path) {
+        let platform = self.emit_platform(crate_path);
 
         let target_dir = get_out_dir();
+        let package = guest_package(metadata, crate_path);
+        let guest = bindings::discover(package);
 
-        let out_dir = std::env::var("OUT_DIR").unwrap();
-        let out_dir = Path::new(&out_dir);
+        // Prebuilt artifacts do not depend on the sources, which are then
+        // neither tracked nor hashed.
+        let sources = self.prebuilt.is_none().then(|| {
+            let sources = Sources::collect(metadata, package, &self.tracked);
+            sources.emit_rerun_directives();
 
+            println!("cargo:rerun-if-env-changed=RUSTFLAGS");
+            sources
+        });
+        let rustflags = env::var("RUSTFLAGS").unwrap_or_default();
+
+        let cache = Cache::new(target_dir.join("univm-cache"));
         let vms = self
             .compilers
             .iter()
-            .map(|compiler| compiler.compile(crate_path, &target_dir).unwrap())
+            .map(|compiler| {
+                let id = compiler.id();
+
+                let artifact = match (&self.prebuilt, &sources) {
+                    (Some(dir), _) => {
+                        prebuilt::load(dir, &guest.name, &id, |elf| compiler.program_id(elf))
+                    }
+                    (None, Some(sources)) => {
+                        let mut inputs = compiler.build_inputs();
+                        inputs.push(format!("RUSTFLAGS={rustflags}"));
+                        let key = sources.key(&platform, &id, &inputs);
+
+                        match cache.load(&guest.name, &id, key) {
+                            Some(artifact) => artifact,
+                            None => {
+                                let artifact = compiler.build(crate_path, &target_dir).unwrap();
+                                cache.store(&guest.name, &id, key, &artifact);
+                                artifact
+                            }
+                        }
+                    }
+                    (None, None) => unreachable!("sources are collected unless prebuilt"),
+                };
+
+                if let Some(dir) = &self.export {
+                    prebuilt::export(dir, &guest.name, &id, &artifact);
+                }
+
+                compiler.emit(&artifact).unwrap()
+            })
             .collect::<Vec<_>>();
 
-        let mut generated_methods = File::create(out_dir.join("methods.rs")).unwrap();
+        let mut generated_methods =
+            File::create(out_dir.join("univm").join(format!("{}.rs", guest.name))).unwrap();
         writeln!(
             generated_methods,
-            r#"macro_rules! impl_program {{
-                ($input: ty, $output: ty, $io: ty, $base_program_name: ident) => {{
+            r#"{shared_items}
+
+            macro_rules! impl_program {{
+                (@program $input: ty, $output: ty, $input_io: ty, $output_io: ty, $base_program_name: ident, $function: ident) => {{
                     univm_interface::compiler::paste! {{
                         {concrete_program_impls}
-                        
+
                         pub enum $base_program_name {{
                             {programs}
                         }}
 
+                        impl $base_program_name {{
+                            /// Selector of the guest function.
+                            pub const SELECTOR: u32 = univm_io::selector::selector(stringify!($function));
+
+                            /// Whether the guest commits the selector after the output - only
+                            /// guests exposing several functions do.
+                            pub const COMMITS_SELECTOR: bool = {commits_selector};
+                        }}
+
                         {builder_impls}
 
                         impl univm_interface::GuestProgram<univm_interface::UniVM> for $base_program_name {{
                             type Input = $input;
                             type Output = $output;
 
-                            fn execute(&self, zkvm: &univm_interface::UniVM, input: Self::Input) -> Result<(Self::Output, univm_interface::UniExecutionReport), ()> {{
+                            fn execute(&self, zkvm: &univm_interface::UniVM, input: &Self::Input) -> Result<(Self::Output, univm_interface::UniExecutionReport), univm_interface::Error> {{
                                 {univm_execute}
                             }}
 
-                            fn prove(&self, zkvm: &univm_interface::UniVM, input: Self::Input) -> Result<(Self::Output, univm_interface::UniProof, univm_interface::UniExecutionReport), ()> {{
+                            fn prove(&self, zkvm: &univm_interface::UniVM, input: &Self::Input) -> Result<(Self::Output, univm_interface::UniProof, Box<dyn univm_interface::ProvingReport>), univm_interface::Error> {{
                                 {univm_prove}
                             }}
 
-                            fn verify(&self, zkvm: &univm_interface::UniVM, proof: &univm_interface::UniProof) -> bool {{
+                            fn verify(&self, zkvm: &univm_interface::UniVM, proof: &univm_interface::UniProof) -> Result<Self::Output, univm_interface::Error> {{
                                 {univm_verify}
                             }}
                         }}
                     }}
                 }};
             }}"#,
+            commits_selector = guest.commits_selector(),
+            shared_items = vms
+                .iter()
+                .map(|CompilationResult { shared_items, .. }| shared_items.as_str())
+                .collect::<String>(),
             concrete_program_impls = vms
                 .iter()
                 .map(|CompilationResult { program_impl, .. }| program_impl.as_str())
@@ -207,9 +304,28 @@ impl BuildOptions {
             )
         )
         .unwrap();
+
+        // Programs of the functions annotated in the guest, typed as seen by
+        // the attribute.
+        for function in &guest.functions {
+            writeln!(
+                generated_methods,
+                "impl_program!(@program {}, {}, {}, {}, {}, {});",
+                function.binding("__Input"),
+                function.binding("__Output"),
+                function.binding("__InputIo"),
+                function.binding("__OutputIo"),
+                function.program_name(),
+                function.name,
+            )
+            .unwrap();
+        }
     }
 
-    pub fn build(self) {
+    /// Builds the guest crates. Each crate's programs go to a module named
+    /// after the crate, all of which are declared by `$OUT_DIR/methods.rs`.
+    /// With a single crate, its programs are also re-exported at the top.
+    pub fn build(mut self) {
         if self.compilers.len() == 0 {
             panic!("No compilers installed - please choose at least one zkvm");
         }
@@ -218,12 +334,131 @@ impl BuildOptions {
             panic!("No crates selected to build - please provide at least one crate to build");
         }
 
+        println!("cargo:rerun-if-env-changed=UNIVM_PREBUILT_DIR");
+        if let Some(dir) = env::var_os("UNIVM_PREBUILT_DIR") {
+            self.prebuilt = Some(resolve_path(Path::new(&dir)));
+        }
+
+        println!("cargo:rerun-if-env-changed=UNIVM_EXPORT_DIR");
+        if let Some(dir) = env::var_os("UNIVM_EXPORT_DIR") {
+            self.export = Some(resolve_path(Path::new(&dir)));
+        }
+
+        let out_dir = std::env::var("OUT_DIR").unwrap();
+        let out_dir = Path::new(&out_dir);
+        fs::create_dir_all(out_dir.join("univm")).unwrap();
+
+        // Crates are checked for duplicates before any of them is built.
+        let mut names = Vec::new();
+        let mut metadata = Vec::new();
         for c in self.crates.iter() {
-            self.build_crate(&c)
+            let crate_metadata = MetadataCommand::new()
+                .manifest_path(c.join("Cargo.toml"))
+                .exec()
+                .unwrap();
+            let name = guest_package(&crate_metadata, c).name.replace('-', "_");
+
+            if names.contains(&name) {
+                panic!("Guest crate {name} added more than once");
+            }
+            names.push(name);
+            metadata.push(crate_metadata);
+        }
+
+        for (c, metadata) in self.crates.iter().zip(&metadata) {
+            self.build_crate(c, metadata, out_dir);
         }
+
+        write_methods(out_dir, &names);
+    }
+}
+
+/// Writes `$OUT_DIR/methods.rs`, declaring a module with the programs of each
+/// of the guest crates `names`.
+fn write_methods(out_dir: &Path, names: &[String]) {
+    let mut methods = File::create(out_dir.join("methods.rs")).unwrap();
+    for name in names.iter() {
+        writeln!(
+            methods,
+            "pub mod {name} {{\n    include!(concat!(env!(\"OUT_DIR\"), \"/univm/{name}.rs\"));\n}}"
+        )
+        .unwrap();
+    }
+
+    if let [name] = names {
+        writeln!(methods, "pub use {name}::*;").unwrap();
+    }
+}
+
+/// Resolves `path` relative to the manifest directory of the built crate.
+fn resolve_path(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        let manifest_dir: PathBuf = std::env::var("CARGO_MANIFEST_DIR").unwrap().into();
+
+        manifest_dir.join(path)
     }
 }
 
+/// Package of the guest crate, out of its workspace.
+fn guest_package<'a>(metadata: &'a Metadata, crate_path: &Path) -> &'a Package {
+    let manifest_path = crate_path.join("Cargo.toml").canonicalize().unwrap();
+
+    metadata
+        .packages
+        .iter()
+        .find(|package| {
+            package
+                .manifest_path
+                .canonicalize()
+                .is_ok_and(|path| path == manifest_path)
+        })
+        .expect("guest crate not found in its workspace")
+}
+
 pub fn new() -> BuildOptions {
     BuildOptions::new()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn methods(names: &[&str]) -> String {
+        let dir = env::temp_dir().join(format!(
+            "univm-methods-{}-{}",
+            std::process::id(),
+            names.len()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+
+        let names = names
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<Vec<_>>();
+        write_methods(&dir, &names);
+        let methods = fs::read_to_string(dir.join("methods.rs")).unwrap();
+
+        fs::remove_dir_all(dir).unwrap();
+        methods
+    }
+
+    #[test]
+    fn declares_module_per_crate() {
+        assert_eq!(
+            methods(&["first", "second"]),
+            "pub mod first {\n    include!(concat!(env!(\"OUT_DIR\"), \"/univm/first.rs\"));\n}\n\
+             pub mod second {\n    include!(concat!(env!(\"OUT_DIR\"), \"/univm/second.rs\"));\n}\n"
+        );
+    }
+
+    #[test]
+    fn reexports_single_crate() {
+        assert_eq!(
+            methods(&["guest"]),
+            "pub mod guest {\n    include!(concat!(env!(\"OUT_DIR\"), \"/univm/guest.rs\"));\n}\n\
+             pub use guest::*;\n"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Guests built ahead of time, e.g. once in CI, for hosts built without zkvm
+//! toolchains. A directory holds, for each guest and compiler id, the ELF
+//! (`<guest>-<id>.elf`) and the program id words (`<guest>-<id>.id`, separated
+//! by whitespace, empty if the zkvm has none).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use univm_interface::compiler::Artifact;
+
+fn paths(dir: &Path, guest: &str, compiler_id: &str) -> (PathBuf, PathBuf) {
+    (
+        dir.join(format!("{guest}-{compiler_id}.elf")),
+        dir.join(format!("{guest}-{compiler_id}.id")),
+    )
+}
+
+/// Loads the guest artifact from `dir`, checking its program id against the
+/// one derived from the ELF by `program_id`, if any.
+pub fn load(
+    dir: &Path,
+    guest: &str,
+    compiler_id: &str,
+    program_id: impl FnOnce(&[u8]) -> Option<Vec<u32>>,
+) -> Artifact {
+    let (elf_path, id_path) = paths(dir, guest, compiler_id);
+    println!("cargo:rerun-if-changed={}", elf_path.display());
+    println!("cargo:rerun-if-changed={}", id_path.display());
+
+    let elf_path = elf_path
+        .canonicalize()
+        .unwrap_or_else(|err| panic!("Prebuilt ELF {} not found: {err}", elf_path.display()));
+    let expected_id: Vec<u32> = fs::read_to_string(&id_path)
+        .unwrap_or_else(|err| panic!("Prebuilt program id {} not found: {err}", id_path.display()))
+        .split_whitespace()
+        .map(|word| word.parse().expect("program id words are u32"))
+        .collect();
+
+    if let Some(actual_id) = program_id(&fs::read(&elf_path).unwrap())
+        && actual_id != expected_id
+    {
+        panic!(
+            "Prebuilt ELF {} does not match its program id {} - export both again",
+            elf_path.display(),
+            id_path.display()
+        );
+    }
+
+    Artifact {
+        elf_path,
+        program_id: expected_id,
+    }
+}
+
+/// Copies the guest artifact to `dir`, to be loaded by later builds. Artifacts
+/// loaded from `dir` itself are left as they are.
+pub fn export(dir: &Path, guest: &str, compiler_id: &str, artifact: &Artifact) {
+    fs::create_dir_all(dir).unwrap();
+
+    let (elf_path, id_path) = paths(dir, guest, compiler_id);
+    // Copying a file onto itself truncates it.
+    if elf_path.canonicalize().ok().as_ref() == Some(&artifact.elf_path.canonicalize().unwrap()) {
+        return;
+    }
+    fs::copy(&artifact.elf_path, elf_path).unwrap();
+
+    let program_id = artifact
+        .program_id
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<_>>()
+        .join(" ");
+    fs::write(id_path, program_id + "\n").unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_exported_artifact() {
+        let dir = std::env::temp_dir().join(format!("univm-prebuilt-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let built = dir.join("built.elf");
+        fs::write(&built, b"elf").unwrap();
+
+        let exported = dir.join("exported");
+        export(
+            &exported,
+            "guest",
+            "risc0-release",
+            &Artifact {
+                elf_path: built,
+                program_id: vec![1, 2, u32::MAX],
+            },
+        );
+
+        let artifact = load(&exported, "guest", "risc0-release", |_| None);
+        assert_eq!(fs::read(&artifact.elf_path).unwrap(), b"elf");
+        assert_eq!(artifact.program_id, [1, 2, u32::MAX]);
+
+        // Exporting to the directory loaded from leaves the ELF intact.
+        export(&exported, "guest", "risc0-release", &artifact);
+        let artifact = load(&exported, "guest", "risc0-release", |elf| {
+            assert_eq!(elf, b"elf");
+            Some(vec![1, 2, u32::MAX])
+        });
+        assert_eq!(fs::read(&artifact.elf_path).unwrap(), b"elf");
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "does not match its program id")]
+    fn rejects_mismatched_program_id() {
+        let dir = std::env::temp_dir().join(format!("univm-prebuilt-id-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let built = dir.join("built.elf");
+        fs::write(&built, b"elf").unwrap();
+        export(
+            &dir,
+            "guest",
+            "risc0-release",
+            &Artifact {
+                elf_path: built,
+                program_id: vec![1, 2],
+            },
+        );
+
+        load(&dir, "guest", "risc0-release", |_| Some(vec![1, 3]));
+    }
+}
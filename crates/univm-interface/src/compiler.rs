@@ -1,11 +1,58 @@
-use std::path::Path;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
 
 pub use paste::paste;
 
 pub trait Compiler {
-    fn compile(&self, crate_path: &Path, target_path: &Path) -> Result<CompilationResult, ()>;
+    /// Identifies the zkvm and the options guests are built with. Artifacts
+    /// are only reused when built by a compiler with the same id.
+    fn id(&self) -> String;
+
+    /// Builds the guest crate.
+    fn build(&self, crate_path: &Path, target_path: &Path) -> Result<Artifact, ()>;
+
+    /// Emits the programs of an artifact, built now or reused from a previous
+    /// build.
+    fn emit(&self, artifact: &Artifact) -> Result<CompilationResult, ()>;
 
     fn emit_platform(&self) -> Result<String, ()>;
+
+    /// Inputs of the guest build besides its sources and the id, such as the
+    /// toolchain version and build options. Artifacts are only reused when
+    /// built with the same inputs.
+    fn build_inputs(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Derives the program id from the ELF, for zkvms whose program id is
+    /// derived at build time. Prebuilt artifacts are checked against it.
+    fn program_id(&self, _elf: &[u8]) -> Option<Vec<u32>> {
+        None
+    }
+}
+
+/// Version of the rustup `toolchain`, as reported by `rustc --version`, or an
+/// empty string if it is not installed.
+pub fn toolchain_version(toolchain: &str) -> String {
+    Command::new("rustc")
+        .arg("--version")
+        .env("RUSTUP_TOOLCHAIN", toolchain)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_owned())
+        .unwrap_or_default()
+}
+
+/// Guest built for a zkvm.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub elf_path: PathBuf,
+
+    /// Program id, if the zkvm derives it at build time.
+    pub program_id: Vec<u32>,
 }
 
 pub struct CompilationResult {
@@ -18,4 +65,7 @@ pub struct CompilationResult {
     pub program_name: String,
 
     pub program_impl: String,
+
+    /// Items emitted once per guest crate and shared by all of its programs.
+    pub shared_items: String,
 }
@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("guest panicked at {location} after {cycles} cycles: {message}")]
+    GuestPanicked {
+        message: String,
+        location: String,
+        cycles: u64,
+    },
+
+    #[error("failed to encode guest input: {0}")]
+    Serialize(BoxedError),
+
+    #[error("failed to decode guest output: {0}")]
+    Deserialize(BoxedError),
+
+    #[error("proof verification failed: {0}")]
+    Verification(BoxedError),
+
+    #[error("output was committed by guest function {actual:#010x}, expected {expected:#010x}")]
+    SelectorMismatch { expected: u32, actual: u32 },
+
+    #[error("zkvm backend error: {0}")]
+    Backend(BoxedError),
+}
+
+impl Error {
+    pub fn serialize(error: impl Into<BoxedError>) -> Self {
+        Self::Serialize(error.into())
+    }
+
+    pub fn deserialize(error: impl Into<BoxedError>) -> Self {
+        Self::Deserialize(error.into())
+    }
+
+    pub fn verification(error: impl Into<BoxedError>) -> Self {
+        Self::Verification(error.into())
+    }
+
+    pub fn backend(error: impl Into<BoxedError>) -> Self {
+        Self::Backend(error.into())
+    }
+}
@@ -2,6 +2,21 @@ use std::any::Any;
 
 pub mod compiler;
 
+mod error;
+pub use error::*;
+
+mod log;
+pub use log::*;
+
+mod output;
+pub use output::*;
+
+mod panic;
+pub use panic::*;
+
+mod selector;
+pub use selector::*;
+
 pub trait ZkvmMethods {
     fn name(&self) -> &'static str;
 }
@@ -16,12 +31,24 @@ pub enum ProofKind {
 
 #[auto_impl::auto_impl(&, Box)]
 pub trait Proof {
+    /// Output committed by the guest - the encoded output, followed by the
+    /// selector of the guest function (little-endian `u32`) if the guest
+    /// exposes several functions.
     fn claim(&self) -> &[u8];
 }
 
 #[auto_impl::auto_impl(&, Box)]
 pub trait ExecutionReport {
     fn cycles(&self) -> u64;
+
+    /// Log records emitted by the guest, in order.
+    fn logs(&self) -> &[LogRecord];
+
+    /// Everything the guest wrote to its standard output.
+    fn stdout(&self) -> &[u8];
+
+    /// Everything the guest wrote to its standard error.
+    fn stderr(&self) -> &[u8];
 }
 
 #[auto_impl::auto_impl(&, Box)]
@@ -46,17 +73,18 @@ pub trait GuestProgram<T: Zkvm> {
     fn execute(
         &self,
         zkvm: &T,
-        input: Self::Input,
-    ) -> Result<(Self::Output, T::ExecutionReport), ()>;
+        input: &Self::Input,
+    ) -> Result<(Self::Output, T::ExecutionReport), Error>;
 
+    /// Proves a run of the guest. Logs, stdout and stderr of the guest are
+    /// only collected by [`GuestProgram::execute`] and discarded here.
     fn prove(
         &self,
         zkvm: &T,
-        input: Self::Input,
-    ) -> Result<(Self::Output, T::Proof, T::ProvingReport), ()>;
+        input: &Self::Input,
+    ) -> Result<(Self::Output, T::Proof, T::ProvingReport), Error>;
 
-    #[must_use]
-    fn verify(&self, zkvm: &T, proof: &T::Proof) -> Result<Self::Output, ()>;
+    fn verify(&self, zkvm: &T, proof: &T::Proof) -> Result<Self::Output, Error>;
 }
 
 pub struct UniProof(Box<dyn Proof>);
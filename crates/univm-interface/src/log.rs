@@ -0,0 +1,83 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+/// Level of a guest log record, shared with the guest platform.
+pub use univm_io::log::Level as LogLevel;
+
+/// Log line emitted by the guest through `univm_platform::log`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+}
+
+impl LogRecord {
+    /// Decodes a record written by the guest platform - a level byte followed
+    /// by the message.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let (level, message) = bytes.split_first()?;
+
+        Some(Self {
+            level: LogLevel::from_u8(*level)?,
+            message: String::from_utf8_lossy(message).into_owned(),
+        })
+    }
+}
+
+/// Collects guest log records written by the backend, one record per write.
+#[derive(Debug, Clone, Default)]
+pub struct LogCollector(Arc<Mutex<Vec<LogRecord>>>);
+
+impl LogCollector {
+    pub fn push(&self, record: &[u8]) {
+        if let Some(record) = LogRecord::decode(record) {
+            self.0.lock().unwrap().push(record);
+        }
+    }
+
+    pub fn take(&self) -> Vec<LogRecord> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+impl io::Write for LogCollector {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use univm_io::log::encode_record;
+
+    use super::*;
+
+    #[test]
+    fn decodes_records_encoded_by_guest() {
+        for level in [
+            LogLevel::Error,
+            LogLevel::Warn,
+            LogLevel::Info,
+            LogLevel::Debug,
+        ] {
+            assert_eq!(
+                LogRecord::decode(&encode_record(level, "adding 1 and 2")),
+                Some(LogRecord {
+                    level,
+                    message: "adding 1 and 2".to_owned(),
+                })
+            );
+        }
+
+        assert_eq!(LogRecord::decode(&[]), None);
+        assert_eq!(LogRecord::decode(&[0, b'a']), None);
+        assert_eq!(LogRecord::decode(&[5, b'a']), None);
+    }
+}
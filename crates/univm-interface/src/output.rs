@@ -0,0 +1,108 @@
+use std::{
+    fmt, io,
+    sync::{Arc, Mutex},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Receives guest output as it is produced, chunk by chunk.
+pub type OutputCallback = Arc<dyn Fn(OutputStream, &[u8]) + Send + Sync>;
+
+/// Buffers one guest output stream, forwarding every chunk to the callback if
+/// one is set.
+#[derive(Clone)]
+pub struct OutputCapture {
+    stream: OutputStream,
+    buffer: Arc<Mutex<Vec<u8>>>,
+    callback: Option<OutputCallback>,
+}
+
+impl OutputCapture {
+    pub fn new(stream: OutputStream, callback: Option<OutputCallback>) -> Self {
+        Self {
+            stream,
+            buffer: Arc::default(),
+            callback,
+        }
+    }
+
+    pub fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.buffer.lock().unwrap())
+    }
+}
+
+impl fmt::Debug for OutputCapture {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutputCapture")
+            .field("stream", &self.stream)
+            .field("buffer", &self.buffer)
+            .finish_non_exhaustive()
+    }
+}
+
+impl io::Write for OutputCapture {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.lock().unwrap().extend_from_slice(buf);
+
+        if let Some(callback) = &self.callback {
+            callback(self.stream, buf);
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    #[test]
+    fn captures_lines_split_across_writes() {
+        let mut capture = OutputCapture::new(OutputStream::Stdout, None);
+        capture.write_all(b"adding 1 and 2\nsum ").unwrap();
+        capture.write_all(b"is 3\n").unwrap();
+
+        assert_eq!(capture.take(), b"adding 1 and 2\nsum is 3\n");
+        assert_eq!(capture.take(), b"");
+    }
+
+    #[test]
+    fn delivers_chunks_to_callback() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let callback: OutputCallback = {
+            let received = received.clone();
+            Arc::new(move |stream, chunk| {
+                received.lock().unwrap().push((stream, chunk.to_vec()));
+            })
+        };
+
+        let mut stdout = OutputCapture::new(OutputStream::Stdout, Some(callback.clone()));
+        let mut stderr = OutputCapture::new(OutputStream::Stderr, Some(callback));
+        stdout.write_all(b"partial ").unwrap();
+        stderr.write_all(b"error\n").unwrap();
+        stdout.write_all(b"line\n").unwrap();
+
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                (OutputStream::Stdout, b"partial ".to_vec()),
+                (OutputStream::Stderr, b"error\n".to_vec()),
+                (OutputStream::Stdout, b"line\n".to_vec()),
+            ]
+        );
+
+        // Chunks are still captured for the report.
+        assert_eq!(stdout.take(), b"partial line\n");
+        assert_eq!(stderr.take(), b"error\n");
+    }
+}
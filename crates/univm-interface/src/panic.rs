@@ -0,0 +1,131 @@
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
+
+use crate::Error;
+
+/// Panic details reported by the guest through `univm_platform::panic`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuestPanic {
+    pub message: String,
+    pub location: String,
+    pub cycles: u64,
+}
+
+impl GuestPanic {
+    /// Decodes a report written by the guest platform - cycle count (`u64`),
+    /// location length (`u32`), location and message, integers little-endian.
+    pub fn decode(bytes: &[u8]) -> Option<Self> {
+        let (cycles, rest) = bytes.split_first_chunk::<8>()?;
+        let (location_len, rest) = rest.split_first_chunk::<4>()?;
+        let location_len = u32::from_le_bytes(*location_len) as usize;
+
+        if rest.len() < location_len {
+            return None;
+        }
+
+        let (location, message) = rest.split_at(location_len);
+
+        Some(Self {
+            message: String::from_utf8_lossy(message).into_owned(),
+            location: String::from_utf8_lossy(location).into_owned(),
+            cycles: u64::from_le_bytes(*cycles),
+        })
+    }
+}
+
+impl From<GuestPanic> for Error {
+    fn from(panic: GuestPanic) -> Self {
+        Self::GuestPanicked {
+            message: panic.message,
+            location: panic.location,
+            cycles: panic.cycles,
+        }
+    }
+}
+
+/// Keeps the last panic report written by the backend.
+#[derive(Debug, Clone, Default)]
+pub struct PanicCollector(Arc<Mutex<Option<GuestPanic>>>);
+
+impl PanicCollector {
+    pub fn push(&self, report: &[u8]) {
+        if let Some(panic) = GuestPanic::decode(report) {
+            self.set(panic);
+        }
+    }
+
+    pub fn set(&self, panic: GuestPanic) {
+        *self.0.lock().unwrap() = Some(panic);
+    }
+
+    pub fn take(&self) -> Option<GuestPanic> {
+        self.0.lock().unwrap().take()
+    }
+
+    /// Turns a backend failure into [`Error::GuestPanicked`] if the guest
+    /// reported a panic, or into [`Error::Backend`] otherwise.
+    pub fn into_error(&self, error: impl Into<crate::BoxedError>) -> Error {
+        match self.take() {
+            Some(panic) => panic.into(),
+            None => Error::backend(error),
+        }
+    }
+}
+
+impl io::Write for PanicCollector {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use univm_io::panic::encode_report;
+
+    use super::*;
+
+    #[test]
+    fn decodes_reports_encoded_by_guest() {
+        let report = encode_report("attempt to add with overflow", "src/lib.rs:22:40", 1234);
+
+        assert_eq!(
+            GuestPanic::decode(&report),
+            Some(GuestPanic {
+                message: "attempt to add with overflow".to_owned(),
+                location: "src/lib.rs:22:40".to_owned(),
+                cycles: 1234,
+            })
+        );
+
+        let report = encode_report("", "", u64::MAX);
+        assert_eq!(
+            GuestPanic::decode(&report),
+            Some(GuestPanic {
+                message: String::new(),
+                location: String::new(),
+                cycles: u64::MAX,
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_reports() {
+        let report = encode_report("message", "location", 1);
+
+        // Truncated cycle count or location length.
+        assert_eq!(GuestPanic::decode(&report[..7]), None);
+        assert_eq!(GuestPanic::decode(&report[..11]), None);
+
+        // Location longer than the rest of the report.
+        let mut report = report;
+        report[8..12].copy_from_slice(&100u32.to_le_bytes());
+        assert_eq!(GuestPanic::decode(&report), None);
+    }
+}
@@ -0,0 +1,59 @@
+use crate::Error;
+
+/// Splits off the selector guests exposing several functions commit after the
+/// output, checking that the output was produced by the function with
+/// `selector`. Functions of the same guest share the program id, so their
+/// proofs are only told apart by the committed selector. Single-function
+/// guests commit none, `selector` is then `None`.
+pub fn strip_selector(selector: Option<u32>, claim: &[u8]) -> Result<&[u8], Error> {
+    let Some(selector) = selector else {
+        return Ok(claim);
+    };
+
+    let (output, actual) = claim
+        .split_last_chunk::<4>()
+        .ok_or_else(|| Error::deserialize("missing function selector"))?;
+    let actual = u32::from_le_bytes(*actual);
+
+    if actual != selector {
+        return Err(Error::SelectorMismatch {
+            expected: selector,
+            actual,
+        });
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_committed_selector() {
+        assert_eq!(
+            strip_selector(Some(7), b"output\x07\0\0\0").unwrap(),
+            b"output"
+        );
+        assert_eq!(strip_selector(None, b"output").unwrap(), b"output");
+    }
+
+    #[test]
+    fn rejects_missing_selector() {
+        assert!(matches!(
+            strip_selector(Some(7), b"\x07\0\0"),
+            Err(Error::Deserialize(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_mismatched_selector() {
+        assert!(matches!(
+            strip_selector(Some(7), b"output\x08\0\0\0"),
+            Err(Error::SelectorMismatch {
+                expected: 7,
+                actual: 8
+            })
+        ));
+    }
+}
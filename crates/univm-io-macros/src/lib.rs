@@ -0,0 +1,131 @@
+use proc_macro::TokenStream;
+use quote::{ToTokens, quote};
+use syn::{Data, DeriveInput, Fields, parse_macro_input, parse_quote, spanned::Spanned};
+
+/// Whether the field is marked with `#[schema(opaque)]` - its type is then
+/// fingerprinted by its spelling, instead of requiring it to implement
+/// `Schema`.
+fn is_opaque(field: &syn::Field) -> syn::Result<bool> {
+    let mut opaque = false;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("schema"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("opaque") {
+                opaque = true;
+                Ok(())
+            } else {
+                Err(meta.error("Unknown schema attribute, expected `opaque`"))
+            }
+        })?;
+    }
+
+    Ok(opaque)
+}
+
+/// Describes the fields, and emits the statements folding their fingerprints
+/// into `fingerprint`.
+fn emit_fields(
+    fields: &Fields,
+    description: &mut String,
+    steps: &mut Vec<proc_macro2::TokenStream>,
+) -> syn::Result<()> {
+    let (open, close) = match fields {
+        Fields::Named(_) => ('{', '}'),
+        Fields::Unnamed(_) => ('(', ')'),
+        Fields::Unit => return Ok(()),
+    };
+
+    description.push(open);
+
+    for (index, field) in fields.iter().enumerate() {
+        match &field.ident {
+            Some(ident) => description.push_str(&format!("{ident},")),
+            None => description.push_str(&format!("{index},")),
+        }
+
+        let ty = &field.ty;
+
+        steps.push(if is_opaque(field)? {
+            let spelling = ty.to_token_stream().to_string();
+
+            quote! {
+                fingerprint = ::univm_io::schema::combine(
+                    fingerprint,
+                    ::univm_io::schema::fingerprint(#spelling.as_bytes()),
+                );
+            }
+        } else {
+            quote! {
+                fingerprint = ::univm_io::schema::combine(
+                    fingerprint,
+                    <#ty as ::univm_io::schema::Schema>::FINGERPRINT,
+                );
+            }
+        });
+    }
+
+    description.push(close);
+
+    Ok(())
+}
+
+fn emit_schema(mut input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let name = &input.ident;
+
+    let mut description = String::new();
+    let mut steps = Vec::new();
+
+    match &input.data {
+        Data::Struct(data) => {
+            description.push_str(&format!("struct {name}"));
+            emit_fields(&data.fields, &mut description, &mut steps)?;
+        }
+        Data::Enum(data) => {
+            description.push_str(&format!("enum {name}{{"));
+            for variant in &data.variants {
+                description.push_str(&variant.ident.to_string());
+                emit_fields(&variant.fields, &mut description, &mut steps)?;
+                description.push(',');
+            }
+            description.push('}');
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new(
+                data.union_token.span(),
+                "Schema cannot be derived for unions",
+            ));
+        }
+    }
+
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(parse_quote!(::univm_io::schema::Schema));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::univm_io::schema::Schema for #name #ty_generics #where_clause {
+            const FINGERPRINT: u64 = {
+                let mut fingerprint = ::univm_io::schema::fingerprint(#description.as_bytes());
+                #(#steps)*
+                fingerprint
+            };
+        }
+    })
+}
+
+#[proc_macro_derive(Schema, attributes(schema))]
+pub fn schema(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let stream = match emit_schema(input) {
+        Ok(v) => v,
+        Err(e) => e.into_compile_error(),
+    };
+
+    stream.into()
+}
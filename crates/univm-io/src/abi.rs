@@ -0,0 +1,121 @@
+use alloc::vec::Vec;
+
+use alloy_sol_types::{SolType, SolValue, abi::TokenSeq};
+
+use crate::{Io, Write};
+
+/// Encodes values as Solidity ABI parameter tuples, as produced by
+/// `abi.encode(a, b, ...)`. Committed outputs can then be decoded on-chain
+/// with `abi.decode(claim, (A, B, ...))`, using the fields of the output type.
+/// The selector that guests exposing several functions commit after the
+/// output is ignored by `abi.decode`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct AbiIo;
+
+impl<T> Io<T> for AbiIo
+where
+    T: SolValue + From<<T::SolType as SolType>::RustType>,
+    for<'a> <T::SolType as SolType>::Token<'a>: TokenSeq<'a>,
+{
+    type Error = alloy_sol_types::Error;
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        Ok(value.abi_encode_params())
+    }
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(&value.abi_encode_params());
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let value = T::abi_decode_params_validate(bytes)?;
+
+        // The decoder tolerates trailing bytes and short padding of the last
+        // word, so only accept the canonical encoding of the decoded value.
+        if value.abi_encode_params() != bytes {
+            return Err(alloy_sol_types::Error::custom("non-canonical ABI encoding"));
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_sol_types::{sol, sol_data};
+
+    use super::*;
+
+    sol! {
+        #[derive(Debug, PartialEq)]
+        struct Output {
+            uint64 sum;
+            bytes32 root;
+            bytes memo;
+        }
+    }
+
+    fn output() -> Output {
+        Output {
+            sum: 3,
+            root: [0xaa; 32].into(),
+            memo: vec![1, 2, 3].into(),
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bytes = AbiIo.serialize(&output()).unwrap();
+
+        let decoded: Output = AbiIo.deserialize(&bytes).unwrap();
+        assert_eq!(decoded, output());
+    }
+
+    #[test]
+    fn encodes_parameter_tuple() {
+        let bytes = AbiIo.serialize(&output()).unwrap();
+
+        // Same as `abi.encode(sum, root, memo)` - head words of the three
+        // parameters, followed by the length and data of `memo`.
+        let mut expected = vec![0; 32 * 5];
+        expected[31] = 3;
+        expected[32..64].fill(0xaa);
+        expected[95] = 0x60;
+        expected[127] = 3;
+        expected[128..131].copy_from_slice(&[1, 2, 3]);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn claim_decodes_as_parameters() {
+        // `abi.decode(claim, (uint64, bytes32, bytes))`.
+        type Params = (
+            sol_data::Uint<64>,
+            sol_data::FixedBytes<32>,
+            sol_data::Bytes,
+        );
+
+        let claim = AbiIo.serialize(&output()).unwrap();
+        let (sum, root, memo) = Params::abi_decode_params(&claim).unwrap();
+        assert_eq!((sum, root.0, memo.to_vec()), (3, [0xaa; 32], vec![1, 2, 3]));
+
+        // Followed by the selector committed by guests exposing several
+        // functions.
+        let claim = [claim, 7u32.to_le_bytes().to_vec()].concat();
+        let (sum, root, memo) = Params::abi_decode_params(&claim).unwrap();
+        assert_eq!((sum, root.0, memo.to_vec()), (3, [0xaa; 32], vec![1, 2, 3]));
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut bytes = AbiIo.serialize(&output()).unwrap();
+        bytes.push(0);
+
+        assert!(<AbiIo as Io<Output>>::deserialize(&AbiIo, &bytes).is_err());
+    }
+}
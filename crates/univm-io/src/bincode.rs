@@ -0,0 +1,80 @@
+use bincode::Options;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{Io, IoReader, IoWriter, Read, Write};
+
+/// Encodes serde types with bincode, using fixed-size integers and rejecting
+/// trailing bytes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BincodeIo;
+
+fn options() -> impl Options {
+    bincode::options()
+        .with_fixint_encoding()
+        .reject_trailing_bytes()
+}
+
+impl<T: Serialize + DeserializeOwned> Io<T> for BincodeIo {
+    type Error = bincode::Error;
+
+    const STREAMING: bool = true;
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        options().serialize(value)
+    }
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        options().serialize_into(IoWriter(writer), value)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        options().deserialize(bytes)
+    }
+
+    fn deserialize_from<R: Read + ?Sized>(&self, reader: &mut R) -> Result<T, Self::Error> {
+        let value = options().deserialize_from(IoReader(&mut *reader))?;
+
+        // Readers are not checked for trailing bytes by bincode.
+        if reader.read(&mut [0]) != 0 {
+            return Err(Box::new(bincode::ErrorKind::Custom(
+                "trailing bytes after bincode data".to_owned(),
+            )));
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Input {
+        a: u64,
+        b: u64,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bytes = BincodeIo.serialize(&Input { a: 1, b: 2 }).unwrap();
+        assert_eq!(bytes.len(), 16);
+
+        let input: Input = BincodeIo.deserialize(&bytes).unwrap();
+        assert_eq!(input, Input { a: 1, b: 2 });
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let mut bytes = BincodeIo.serialize(&Input { a: 1, b: 2 }).unwrap();
+        bytes.push(0);
+
+        assert!(<BincodeIo as Io<Input>>::deserialize(&BincodeIo, &bytes).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use alloc::vec::Vec;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use thiserror::Error;
+
+use crate::{Io, IoReader, IoWriter, Read, Write};
+
+/// Encodes Borsh types, as used by Solana tooling. Trailing bytes are
+/// rejected.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BorshIo;
+
+/// Wraps [`borsh::io::Error`], which does not implement [`core::error::Error`]
+/// without `std`.
+#[derive(Debug, Error)]
+#[error("borsh error: {0}")]
+pub struct BorshError(pub borsh::io::Error);
+
+impl From<borsh::io::Error> for BorshError {
+    fn from(error: borsh::io::Error) -> Self {
+        Self(error)
+    }
+}
+
+impl<T: BorshSerialize + BorshDeserialize> Io<T> for BorshIo {
+    type Error = BorshError;
+
+    const STREAMING: bool = true;
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        Ok(borsh::to_vec(value)?)
+    }
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        Ok(value.serialize(&mut IoWriter(writer))?)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        Ok(borsh::from_slice(bytes)?)
+    }
+
+    fn deserialize_from<R: Read + ?Sized>(&self, reader: &mut R) -> Result<T, Self::Error> {
+        Ok(borsh::from_reader(&mut IoReader(reader))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+    struct Transfer {
+        from: [u8; 32],
+        amount: u64,
+        memo: Option<String>,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let transfer = Transfer {
+            from: [7; 32],
+            amount: 1_000,
+            memo: Some("rent".to_owned()),
+        };
+
+        let bytes = BorshIo
+            .serialize(&Transfer {
+                memo: None,
+                ..transfer
+            })
+            .unwrap();
+        assert_eq!(bytes.len(), 32 + 8 + 1);
+
+        let bytes = BorshIo.serialize(&transfer).unwrap();
+        let decoded: Transfer = BorshIo.deserialize(&bytes).unwrap();
+        assert_eq!(decoded.memo.as_deref(), Some("rent"));
+        assert_eq!(decoded.amount, 1_000);
+    }
+}
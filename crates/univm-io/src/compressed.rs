@@ -0,0 +1,71 @@
+use lz4_flex::block::{DecompressError, compress_prepend_size, decompress_size_prepended};
+use thiserror::Error;
+
+use crate::{Io, Write};
+
+/// Compresses values encoded by the wrapped codec with LZ4, shrinking large
+/// inputs on their way to the prover. Decompression runs inside the guest, so
+/// it trades cycles for transferred bytes.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Compressed<I>(pub I);
+
+#[derive(Debug, Error)]
+pub enum CompressedError<E> {
+    #[error("failed to decompress: {0}")]
+    Decompress(#[from] DecompressError),
+
+    #[error("{0}")]
+    Io(E),
+}
+
+impl<T, I: Io<T>> Io<T> for Compressed<I> {
+    type Error = CompressedError<I::Error>;
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        let bytes = self.0.serialize(value).map_err(CompressedError::Io)?;
+        writer.write(&compress_prepend_size(&bytes));
+
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let bytes = decompress_size_prepended(bytes)?;
+
+        self.0.deserialize(&bytes).map_err(CompressedError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::raw::RawIo;
+
+    #[test]
+    fn roundtrip() {
+        let io = Compressed(RawIo);
+        let data = (0..1u64 << 13)
+            .flat_map(|value| (value % 100).to_le_bytes())
+            .collect::<Vec<_>>();
+
+        let bytes = io.serialize(&data).unwrap();
+        assert!(bytes.len() < data.len() / 2);
+
+        let decoded: Vec<u8> = io.deserialize(&bytes).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn rejects_corrupted_data() {
+        let io = Compressed(RawIo);
+
+        let mut bytes = io.serialize(&vec![7; 1024]).unwrap();
+        bytes.truncate(bytes.len() - 1);
+
+        let result: Result<Vec<u8>, _> = io.deserialize(&bytes);
+        assert!(matches!(result, Err(CompressedError::Decompress(_))));
+    }
+}
@@ -1,10 +1,180 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
 #[cfg(feature = "ssz")]
 pub mod ssz;
 
+#[cfg(feature = "bincode")]
+pub mod bincode;
+
+#[cfg(feature = "postcard")]
+pub mod postcard;
+
+#[cfg(feature = "borsh")]
+pub mod borsh;
+
+#[cfg(feature = "rkyv")]
+pub mod rkyv;
+
+#[cfg(feature = "abi")]
+pub mod abi;
+
+#[cfg(feature = "compression")]
+pub mod compressed;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub mod log;
+pub mod panic;
+pub mod raw;
+pub mod result;
+pub mod schema;
+pub mod selector;
+
+// Lets derived `Schema` impls refer to this crate from its own tests.
+#[cfg(test)]
+extern crate self as univm_io;
+
 pub trait Io<T> {
-    type Error: std::error::Error;
+    type Error: core::error::Error + Send + Sync + 'static;
 
-    fn serialize(&self, value: T) -> Result<Vec<u8>, Self::Error>;
+    /// Whether [`Io::deserialize_from`] decodes incrementally, rather than
+    /// buffering the whole input first.
+    const STREAMING: bool = false;
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        let mut bytes = Vec::new();
+        self.serialize_into(value, &mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Serializes `value` into `writer`, possibly in several writes.
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error>;
 
     fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error>;
+
+    /// Deserializes a value from the remaining bytes of `reader`. Codecs able
+    /// to decode incrementally override this, so that the whole encoding never
+    /// has to be buffered - by default it is read into memory first.
+    fn deserialize_from<R: Read + ?Sized>(&self, reader: &mut R) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes);
+
+        self.deserialize(&bytes)
+    }
+}
+
+/// Destination of serialized bytes.
+pub trait Write {
+    fn write(&mut self, bytes: &[u8]);
+}
+
+impl Write for Vec<u8> {
+    fn write(&mut self, bytes: &[u8]) {
+        self.extend_from_slice(bytes);
+    }
+}
+
+/// Source of serialized bytes.
+pub trait Read {
+    /// Reads up to `buf.len()` bytes into `buf`, returning how many were read.
+    /// Returns zero once all bytes were read.
+    fn read(&mut self, buf: &mut [u8]) -> usize;
+
+    /// Appends all remaining bytes to `buf`.
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) {
+        const CHUNK: usize = 4096;
+
+        loop {
+            let len = buf.len();
+            buf.resize(len + CHUNK, 0);
+
+            let read = self.read(&mut buf[len..]);
+            buf.truncate(len + read);
+
+            if read == 0 {
+                break;
+            }
+        }
+    }
+}
+
+impl Read for &[u8] {
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.len());
+        let (head, tail) = self.split_at(len);
+        buf[..len].copy_from_slice(head);
+        *self = tail;
+
+        len
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self);
+        *self = &[];
+    }
+}
+
+/// Reads exactly `buf.len()` bytes, returning whether there were enough.
+pub(crate) fn read_exact<R: Read + ?Sized>(reader: &mut R, mut buf: &mut [u8]) -> bool {
+    while !buf.is_empty() {
+        match reader.read(buf) {
+            0 => return false,
+            read => buf = &mut buf[read..],
+        }
+    }
+
+    true
+}
+
+/// Adapts [`Write`] and [`Read`] to `std::io::Write` and `std::io::Read`, for
+/// codecs using the latter. Without `std`, borsh comes with its own copy of
+/// these traits.
+#[cfg(any(feature = "bincode", feature = "borsh"))]
+pub(crate) struct IoWriter<'a, W: ?Sized>(pub &'a mut W);
+
+#[cfg(any(feature = "bincode", feature = "borsh"))]
+pub(crate) struct IoReader<'a, R: ?Sized>(pub &'a mut R);
+
+#[cfg(feature = "borsh")]
+use ::borsh::io as codec_io;
+#[cfg(all(feature = "bincode", not(feature = "borsh")))]
+use std::io as codec_io;
+
+#[cfg(any(feature = "bincode", feature = "borsh"))]
+impl<W: Write + ?Sized> codec_io::Write for IoWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> codec_io::Result<usize> {
+        self.0.write(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> codec_io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "bincode", feature = "borsh"))]
+impl<R: Read + ?Sized> codec_io::Read for IoReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> codec_io::Result<usize> {
+        Ok(self.0.read(buf))
+    }
+}
+
+/// Codecs able to access encoded values in place, without deserializing them.
+pub trait View<T: ?Sized> {
+    type Error: core::error::Error;
+
+    /// Alignment the buffer passed to [`View::view`] must have.
+    const ALIGN: usize = 1;
+
+    fn view<'a>(&self, bytes: &'a [u8]) -> Result<&'a T, Self::Error>;
 }
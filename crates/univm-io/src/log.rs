@@ -0,0 +1,34 @@
+//! Encoding of guest log records, shared by the guest platform and the host.
+
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl Level {
+    /// Level of an encoded record, see [`encode_record`].
+    pub fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(Self::Error),
+            2 => Some(Self::Warn),
+            3 => Some(Self::Info),
+            4 => Some(Self::Debug),
+            _ => None,
+        }
+    }
+}
+
+/// Encodes a record as sent to the host: level byte, followed by the message.
+pub fn encode_record(level: Level, message: &str) -> Vec<u8> {
+    let mut record = Vec::with_capacity(message.len() + 1);
+    record.push(level as u8);
+    record.extend_from_slice(message.as_bytes());
+
+    record
+}
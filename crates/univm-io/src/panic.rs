@@ -0,0 +1,15 @@
+//! Encoding of guest panic reports, shared by the guest platform and the host.
+
+use alloc::vec::Vec;
+
+/// Encodes a panic report as sent to the host: cycle count (`u64`), location
+/// length (`u32`), location and message, integers little-endian.
+pub fn encode_report(message: &str, location: &str, cycles: u64) -> Vec<u8> {
+    let mut report = Vec::with_capacity(12 + location.len() + message.len());
+    report.extend_from_slice(&cycles.to_le_bytes());
+    report.extend_from_slice(&(location.len() as u32).to_le_bytes());
+    report.extend_from_slice(location.as_bytes());
+    report.extend_from_slice(message.as_bytes());
+
+    report
+}
@@ -0,0 +1,106 @@
+use alloc::vec::Vec;
+
+use serde::{Serialize, de::DeserializeOwned};
+use thiserror::Error;
+
+use crate::{Io, Write};
+
+/// Encodes serde types with postcard - a compact, varint-based format which
+/// does not require `std`. Trailing bytes are rejected.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PostcardIo;
+
+#[derive(Debug, Error)]
+pub enum PostcardError {
+    #[error("postcard error: {0}")]
+    Postcard(#[from] postcard::Error),
+
+    #[error("{0} trailing bytes after postcard data")]
+    TrailingBytes(usize),
+}
+
+impl<T: Serialize + DeserializeOwned> Io<T> for PostcardIo {
+    type Error = PostcardError;
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        Ok(postcard::to_allocvec(value)?)
+    }
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(&postcard::to_allocvec(value)?);
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let (value, rest) = postcard::take_from_bytes(bytes)?;
+
+        if !rest.is_empty() {
+            return Err(PostcardError::TrailingBytes(rest.len()));
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    use super::*;
+    #[cfg(feature = "ssz")]
+    use crate::ssz::SszIo;
+
+    // Same shape as the types of the `sum` example.
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "ssz", derive(ssz::Ssz))]
+    struct Input {
+        a: u64,
+        b: u64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[cfg_attr(feature = "ssz", derive(ssz::Ssz))]
+    struct Output {
+        sum: u64,
+    }
+
+    #[test]
+    fn roundtrip() {
+        // Fixed encoding - guests decode the exact same bytes.
+        let bytes = PostcardIo.serialize(&Input { a: 1, b: 300 }).unwrap();
+        assert_eq!(bytes, [0x01, 0xac, 0x02]);
+
+        let input: Input = PostcardIo.deserialize(&bytes).unwrap();
+        assert_eq!(input, Input { a: 1, b: 300 });
+
+        let bytes = PostcardIo.serialize(&Output { sum: u64::MAX }).unwrap();
+        let output: Output = PostcardIo.deserialize(&bytes).unwrap();
+        assert_eq!(output, Output { sum: u64::MAX });
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        let result: Result<Output, _> = PostcardIo.deserialize(&[0x03, 0x00]);
+        assert!(matches!(result, Err(PostcardError::TrailingBytes(1))));
+    }
+
+    #[cfg(feature = "ssz")]
+    #[test]
+    fn smaller_than_ssz() {
+        let postcard = PostcardIo.serialize(&Input { a: 1, b: 2 }).unwrap();
+        let ssz = SszIo.serialize(&Input { a: 1, b: 2 }).unwrap();
+        assert_eq!((postcard.len(), ssz.len()), (2, 16));
+
+        let postcard = PostcardIo.serialize(&Output { sum: 3 }).unwrap();
+        let ssz = SszIo.serialize(&Output { sum: 3 }).unwrap();
+        assert_eq!((postcard.len(), ssz.len()), (1, 8));
+
+        // Varints only pay off for small values.
+        let postcard = PostcardIo.serialize(&Output { sum: u64::MAX }).unwrap();
+        assert_eq!(postcard.len(), 10);
+    }
+}
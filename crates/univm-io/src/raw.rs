@@ -0,0 +1,86 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::convert::Infallible;
+
+use crate::{Io, View, Write};
+
+/// Passes bytes through as they are, for inputs and outputs which are already
+/// encoded. Entrypoints may take `&[u8]` to borrow the input buffer directly.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RawIo;
+
+impl Io<Vec<u8>> for RawIo {
+    type Error = Infallible;
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &Vec<u8>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(value);
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Vec<u8>, Self::Error> {
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Io<Box<[u8]>> for RawIo {
+    type Error = Infallible;
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &Box<[u8]>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(value);
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Box<[u8]>, Self::Error> {
+        Ok(bytes.into())
+    }
+}
+
+impl View<[u8]> for RawIo {
+    type Error = Infallible;
+
+    fn view<'a>(&self, bytes: &'a [u8]) -> Result<&'a [u8], Self::Error> {
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn roundtrip() {
+        let bytes = RawIo.serialize(&vec![1, 2, 3]).unwrap();
+        assert_eq!(bytes, [1, 2, 3]);
+
+        let decoded: Vec<u8> = RawIo.deserialize(&bytes).unwrap();
+        assert_eq!(decoded, [1, 2, 3]);
+
+        let boxed: Box<[u8]> = Box::new([0xff; 4]);
+        let bytes = RawIo.serialize(&boxed).unwrap();
+        assert_eq!(bytes, [0xff; 4]);
+
+        let decoded: Box<[u8]> = RawIo.deserialize(&bytes).unwrap();
+        assert_eq!(decoded, boxed);
+
+        let empty: Vec<u8> = RawIo.deserialize(&[]).unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn view_borrows_input() {
+        let bytes = [4, 5, 6];
+
+        let view = RawIo.view(&bytes).unwrap();
+        assert_eq!(view, bytes);
+        assert_eq!(view.as_ptr(), bytes.as_ptr());
+    }
+}
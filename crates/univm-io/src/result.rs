@@ -0,0 +1,152 @@
+use thiserror::Error;
+
+use crate::{Io, Read, Write, read_exact};
+
+const OK_TAG: u8 = 0;
+const ERR_TAG: u8 = 1;
+
+/// Encodes `Result<T, E>` with the wrapped codec, prefixing the encoded value
+/// with a tag byte - `0` for `Ok`, `1` for `Err`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ResultIo<I>(pub I);
+
+#[derive(Debug, Error)]
+pub enum ResultError<T, E> {
+    #[error("missing result tag")]
+    MissingTag,
+
+    #[error("invalid result tag: {0}")]
+    InvalidTag(u8),
+
+    #[error("failed to process success value: {0}")]
+    Ok(T),
+
+    #[error("failed to process error value: {0}")]
+    Err(E),
+}
+
+impl<T, E, I: Io<T> + Io<E>> Io<Result<T, E>> for ResultIo<I> {
+    type Error = ResultError<<I as Io<T>>::Error, <I as Io<E>>::Error>;
+
+    const STREAMING: bool = <I as Io<T>>::STREAMING && <I as Io<E>>::STREAMING;
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &Result<T, E>,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        match value {
+            Ok(value) => {
+                writer.write(&[OK_TAG]);
+                self.0
+                    .serialize_into(value, writer)
+                    .map_err(ResultError::Ok)
+            }
+            Err(value) => {
+                writer.write(&[ERR_TAG]);
+                self.0
+                    .serialize_into(value, writer)
+                    .map_err(ResultError::Err)
+            }
+        }
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Result<T, E>, Self::Error> {
+        let (&tag, bytes) = bytes.split_first().ok_or(ResultError::MissingTag)?;
+
+        match tag {
+            OK_TAG => Ok(Ok(self.0.deserialize(bytes).map_err(ResultError::Ok)?)),
+            ERR_TAG => Ok(Err(self.0.deserialize(bytes).map_err(ResultError::Err)?)),
+            tag => Err(ResultError::InvalidTag(tag)),
+        }
+    }
+
+    fn deserialize_from<R: Read + ?Sized>(
+        &self,
+        reader: &mut R,
+    ) -> Result<Result<T, E>, Self::Error> {
+        let mut tag = [0];
+        if !read_exact(reader, &mut tag) {
+            return Err(ResultError::MissingTag);
+        }
+
+        match tag[0] {
+            OK_TAG => Ok(Ok(self
+                .0
+                .deserialize_from(reader)
+                .map_err(ResultError::Ok)?)),
+            ERR_TAG => Ok(Err(self
+                .0
+                .deserialize_from(reader)
+                .map_err(ResultError::Err)?)),
+            tag => Err(ResultError::InvalidTag(tag)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{convert::Infallible, string::FromUtf8Error};
+
+    use super::*;
+
+    struct TestIo;
+
+    impl Io<u64> for TestIo {
+        type Error = Infallible;
+
+        fn serialize_into<W: Write + ?Sized>(
+            &self,
+            value: &u64,
+            writer: &mut W,
+        ) -> Result<(), Self::Error> {
+            writer.write(&value.to_le_bytes());
+            Ok(())
+        }
+
+        fn deserialize(&self, bytes: &[u8]) -> Result<u64, Self::Error> {
+            Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+        }
+    }
+
+    impl Io<String> for TestIo {
+        type Error = FromUtf8Error;
+
+        fn serialize_into<W: Write + ?Sized>(
+            &self,
+            value: &String,
+            writer: &mut W,
+        ) -> Result<(), Self::Error> {
+            writer.write(value.as_bytes());
+            Ok(())
+        }
+
+        fn deserialize(&self, bytes: &[u8]) -> Result<String, Self::Error> {
+            String::from_utf8(bytes.to_vec())
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let io = ResultIo(TestIo);
+
+        for value in [Ok(42), Err("block rejected".to_owned())] {
+            let bytes = io.serialize(&value).unwrap();
+            assert_eq!(bytes[0], if value.is_ok() { OK_TAG } else { ERR_TAG });
+
+            let decoded: Result<u64, String> = io.deserialize(&bytes).unwrap();
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_tag() {
+        let io = ResultIo(TestIo);
+
+        let result: Result<Result<u64, String>, _> = io.deserialize(&[]);
+        assert!(matches!(result, Err(ResultError::MissingTag)));
+
+        let result: Result<Result<u64, String>, _> = io.deserialize(&[2, 0]);
+        assert!(matches!(result, Err(ResultError::InvalidTag(2))));
+    }
+}
@@ -0,0 +1,123 @@
+use alloc::vec::Vec;
+
+use rkyv::{
+    Archive, Deserialize, Portable, Serialize,
+    api::high::{HighDeserializer, HighSerializer, HighValidator},
+    bytecheck::CheckBytes,
+    rancor,
+    ser::allocator::ArenaHandle,
+    util::AlignedVec,
+};
+
+use crate::{Io, View, Write};
+
+/// Encodes types with rkyv. Besides deserializing owned values, the encoded
+/// bytes can be validated and accessed in place through [`View`], which saves
+/// the deserialization cost of large guest inputs.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RkyvIo;
+
+/// Alignment of buffers produced by rkyv serializer.
+const ALIGN: usize = 16;
+
+impl<T> Io<T> for RkyvIo
+where
+    T: Archive
+        + for<'a> Serialize<HighSerializer<AlignedVec<ALIGN>, ArenaHandle<'a>, rancor::Error>>,
+    T::Archived: for<'a> CheckBytes<HighValidator<'a, rancor::Error>>
+        + Deserialize<T, HighDeserializer<rancor::Error>>,
+{
+    type Error = rancor::Error;
+
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
+        Ok(rkyv::to_bytes(value)?.into_vec())
+    }
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(&rkyv::to_bytes(value)?);
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        if bytes.as_ptr().align_offset(ALIGN) == 0 {
+            return rkyv::from_bytes(bytes);
+        }
+
+        // Archived data must be aligned, which is not guaranteed for the
+        // buffers handed over by zkvms.
+        let mut aligned = AlignedVec::<ALIGN>::with_capacity(bytes.len());
+        aligned.extend_from_slice(bytes);
+
+        rkyv::from_bytes(&aligned)
+    }
+}
+
+impl<T> View<T> for RkyvIo
+where
+    T: Portable + for<'a> CheckBytes<HighValidator<'a, rancor::Error>>,
+{
+    type Error = rancor::Error;
+
+    const ALIGN: usize = ALIGN;
+
+    fn view<'a>(&self, bytes: &'a [u8]) -> Result<&'a T, Self::Error> {
+        rkyv::access(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rkyv::Archived;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Archive, Serialize, Deserialize)]
+    struct Block {
+        number: u64,
+        transactions: Vec<Vec<u8>>,
+    }
+
+    fn block() -> Block {
+        Block {
+            number: 42,
+            transactions: vec![vec![1, 2, 3], vec![], vec![0xff; 100]],
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let bytes = RkyvIo.serialize(&block()).unwrap();
+
+        let decoded: Block = RkyvIo.deserialize(&bytes).unwrap();
+        assert_eq!(decoded, block());
+
+        // Unaligned buffers are copied before decoding.
+        let mut shifted = vec![0];
+        shifted.extend_from_slice(&bytes);
+        let decoded: Block = RkyvIo.deserialize(&shifted[1..]).unwrap();
+        assert_eq!(decoded, block());
+    }
+
+    #[test]
+    fn view() {
+        let bytes = RkyvIo.serialize(&block()).unwrap();
+        let mut aligned = AlignedVec::<ALIGN>::new();
+        aligned.extend_from_slice(&bytes);
+
+        let view: &Archived<Block> = RkyvIo.view(&aligned).unwrap();
+        assert_eq!(view.number, 42);
+        assert_eq!(view.transactions[2].len(), 100);
+    }
+
+    #[test]
+    fn view_rejects_invalid_data() {
+        let mut aligned = AlignedVec::<ALIGN>::new();
+        aligned.extend_from_slice(&[0xff; 8]);
+
+        assert!(<RkyvIo as View<Archived<Block>>>::view(&RkyvIo, &aligned).is_err());
+    }
+}
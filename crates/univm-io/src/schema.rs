@@ -0,0 +1,266 @@
+//! Fingerprints of type definitions, for detecting host and guest disagreeing
+//! on the layout of inputs and outputs - e.g. because of a stale ELF.
+//!
+//! Types opt in by deriving [`Schema`] (with the `derive` feature), and
+//! programs by wrapping their codec in [`Fingerprinted`].
+
+use alloc::{boxed::Box, string::String, vec::Vec};
+
+use thiserror::Error;
+
+use crate::{Io, Read, Write, read_exact};
+
+#[cfg(feature = "derive")]
+pub use univm_io_macros::Schema;
+
+/// Type with a fingerprint of its definition. Derived fingerprints cover the
+/// type name, field and variant names, and fingerprints of field types.
+pub trait Schema {
+    const FINGERPRINT: u64;
+}
+
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+const fn fnv(mut hash: u64, bytes: &[u8]) -> u64 {
+    let mut i = 0;
+    while i < bytes.len() {
+        hash ^= bytes[i] as u64;
+        hash = hash.wrapping_mul(FNV_PRIME);
+        i += 1;
+    }
+
+    hash
+}
+
+/// FNV-1a hash of `bytes`.
+pub const fn fingerprint(bytes: &[u8]) -> u64 {
+    fnv(FNV_OFFSET, bytes)
+}
+
+/// Folds fingerprint `other` into `fingerprint`.
+pub const fn combine(fingerprint: u64, other: u64) -> u64 {
+    fnv(fingerprint, &other.to_le_bytes())
+}
+
+macro_rules! impl_primitive {
+    ($($ty: ty),*) => {
+        $(
+            impl Schema for $ty {
+                const FINGERPRINT: u64 = fingerprint(stringify!($ty).as_bytes());
+            }
+        )*
+    };
+}
+
+impl_primitive!(
+    (),
+    bool,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    char,
+    String
+);
+
+macro_rules! impl_container {
+    ($($name: literal => $ty: ty),*) => {
+        $(
+            impl<T: Schema> Schema for $ty {
+                const FINGERPRINT: u64 = combine(fingerprint($name.as_bytes()), T::FINGERPRINT);
+            }
+        )*
+    };
+}
+
+impl_container!(
+    "Vec" => Vec<T>,
+    "Option" => Option<T>,
+    "Box" => Box<T>,
+    "slice" => [T]
+);
+
+impl<T: Schema, const N: usize> Schema for [T; N] {
+    const FINGERPRINT: u64 = combine(combine(fingerprint(b"array"), T::FINGERPRINT), N as u64);
+}
+
+impl<T: Schema, E: Schema> Schema for Result<T, E> {
+    const FINGERPRINT: u64 = combine(
+        combine(fingerprint(b"Result"), T::FINGERPRINT),
+        E::FINGERPRINT,
+    );
+}
+
+/// Prefixes values encoded by the wrapped codec with the fingerprint of their
+/// type, and checks it when decoding.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Fingerprinted<I>(pub I);
+
+#[derive(Debug, Error)]
+pub enum FingerprintError<E> {
+    #[error("missing schema fingerprint")]
+    Missing,
+
+    #[error("schema mismatch: expected fingerprint {expected:#018x}, got {actual:#018x}")]
+    Mismatch { expected: u64, actual: u64 },
+
+    #[error("{0}")]
+    Io(E),
+}
+
+impl<T: Schema, I: Io<T>> Io<T> for Fingerprinted<I> {
+    type Error = FingerprintError<I::Error>;
+
+    const STREAMING: bool = I::STREAMING;
+
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(&T::FINGERPRINT.to_le_bytes());
+        self.0
+            .serialize_into(value, writer)
+            .map_err(FingerprintError::Io)
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
+        let (actual, bytes) = bytes
+            .split_first_chunk::<8>()
+            .ok_or(FingerprintError::Missing)?;
+        check_fingerprint::<T, _>(actual)?;
+
+        self.0.deserialize(bytes).map_err(FingerprintError::Io)
+    }
+
+    fn deserialize_from<R: Read + ?Sized>(&self, reader: &mut R) -> Result<T, Self::Error> {
+        let mut actual = [0; 8];
+        if !read_exact(reader, &mut actual) {
+            return Err(FingerprintError::Missing);
+        }
+        check_fingerprint::<T, _>(&actual)?;
+
+        self.0
+            .deserialize_from(reader)
+            .map_err(FingerprintError::Io)
+    }
+}
+
+fn check_fingerprint<T: Schema, E>(actual: &[u8; 8]) -> Result<(), FingerprintError<E>> {
+    let actual = u64::from_le_bytes(*actual);
+
+    if actual != T::FINGERPRINT {
+        return Err(FingerprintError::Mismatch {
+            expected: T::FINGERPRINT,
+            actual,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use univm_io_macros::Schema;
+
+    use super::*;
+    use crate::raw::RawIo;
+
+    mod host {
+        use super::*;
+
+        #[derive(Schema)]
+        #[allow(dead_code)]
+        pub struct Input {
+            pub a: u64,
+            pub b: u64,
+        }
+    }
+
+    mod guest {
+        use super::*;
+
+        #[derive(Schema)]
+        #[allow(dead_code)]
+        pub struct Input {
+            pub a: u64,
+            pub b: u32,
+        }
+    }
+
+    #[derive(Schema)]
+    #[allow(dead_code)]
+    enum Message<T> {
+        Ping,
+        Data(T),
+        Batch {
+            items: Vec<T>,
+            #[schema(opaque)]
+            tag: std::num::NonZeroU8,
+        },
+    }
+
+    #[derive(Debug)]
+    struct Bytes(Vec<u8>);
+
+    impl Schema for Bytes {
+        const FINGERPRINT: u64 = fingerprint(b"Bytes");
+    }
+
+    impl Io<Bytes> for RawIo {
+        type Error = std::convert::Infallible;
+
+        fn serialize_into<W: Write + ?Sized>(
+            &self,
+            value: &Bytes,
+            writer: &mut W,
+        ) -> Result<(), Self::Error> {
+            writer.write(&value.0);
+            Ok(())
+        }
+
+        fn deserialize(&self, bytes: &[u8]) -> Result<Bytes, Self::Error> {
+            Ok(Bytes(bytes.to_vec()))
+        }
+    }
+
+    #[test]
+    fn fingerprints_follow_definitions() {
+        assert_ne!(host::Input::FINGERPRINT, guest::Input::FINGERPRINT);
+        assert_ne!(Message::<u8>::FINGERPRINT, Message::<u16>::FINGERPRINT);
+        assert_eq!(Vec::<u8>::FINGERPRINT, Vec::<u8>::FINGERPRINT);
+        assert_ne!(<[u8; 4]>::FINGERPRINT, <[u8; 8]>::FINGERPRINT);
+    }
+
+    #[test]
+    fn checks_fingerprint() {
+        let io = Fingerprinted(RawIo);
+
+        let bytes = io.serialize(&Bytes(vec![1, 2, 3])).unwrap();
+        assert_eq!(bytes[..8], Bytes::FINGERPRINT.to_le_bytes());
+
+        let decoded: Bytes = io.deserialize(&bytes).unwrap();
+        assert_eq!(decoded.0, [1, 2, 3]);
+
+        let mut stale = bytes.clone();
+        stale[0] ^= 1;
+        let error = <Fingerprinted<RawIo> as Io<Bytes>>::deserialize(&io, &stale).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "schema mismatch: expected fingerprint {:#018x}, got {:#018x}",
+                Bytes::FINGERPRINT,
+                Bytes::FINGERPRINT ^ 1
+            )
+        );
+    }
+}
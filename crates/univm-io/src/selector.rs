@@ -0,0 +1,37 @@
+//! Selectors of guest functions. The host sends the selector ahead of the
+//! input, so that a guest exposing several functions knows which one to run.
+
+use crate::schema::fingerprint;
+
+/// Selector of function `name` - its FNV-1a hash, truncated to 32 bits. Only
+/// the last path segment of `name` is hashed, so that the guest can refer to
+/// functions by path, and the host by name.
+pub const fn selector(name: &str) -> u32 {
+    let mut name = name.as_bytes();
+
+    let mut start = name.len();
+    while start > 0 && name[start - 1] != b':' {
+        start -= 1;
+    }
+    name = name.split_at(start).1.trim_ascii();
+
+    fingerprint(name) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_module_path() {
+        assert_eq!(
+            selector("guest::state_transition"),
+            selector("state_transition")
+        );
+        assert_eq!(
+            selector("guest :: state_transition"),
+            selector("state_transition")
+        );
+        assert_ne!(selector("state_transition"), selector("difference"));
+    }
+}
@@ -1,8 +1,11 @@
+use alloc::vec::Vec;
+
 use ssz::{ReadError, SszReadDefault, SszWrite, WriteError};
 use thiserror::Error;
 
-use crate::Io;
+use crate::{Io, Write};
 
+#[derive(Debug, Default, Clone, Copy)]
 pub struct SszIo;
 
 #[derive(Debug, Error)]
@@ -17,10 +20,19 @@ pub enum SszError {
 impl<T: SszReadDefault + SszWrite> Io<T> for SszIo {
     type Error = SszError;
 
-    fn serialize(&self, value: T) -> Result<Vec<u8>, Self::Error> {
+    fn serialize(&self, value: &T) -> Result<Vec<u8>, Self::Error> {
         Ok(value.to_ssz()?)
     }
 
+    fn serialize_into<W: Write + ?Sized>(
+        &self,
+        value: &T,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(&value.to_ssz()?);
+        Ok(())
+    }
+
     fn deserialize(&self, bytes: &[u8]) -> Result<T, Self::Error> {
         Ok(T::from_ssz_default(bytes)?)
     }
@@ -0,0 +1,181 @@
+//! Conformance checks for [`Io`] implementations. Instantiate them for a codec
+//! and a [`proptest`] strategy of values with [`io_conformance!`]:
+//!
+//! ```ignore
+//! univm_io::io_conformance!(ssz_u64, SszIo, any::<u64>());
+//!
+//! // Every byte string is a valid encoding.
+//! univm_io::io_conformance!(raw, RawIo, any::<Vec<u8>>(), unchecked(rejects_truncated));
+//! ```
+//!
+//! [`io_conformance!`]: crate::io_conformance
+
+use std::fmt::Debug;
+
+#[doc(hidden)]
+pub use proptest;
+use proptest::{prop_assert, prop_assert_eq, test_runner::TestCaseError};
+
+use crate::{Io, Read};
+
+/// Decoding the encoding of `value` yields `value`.
+pub fn roundtrip<T: PartialEq + Debug, I: Io<T>>(io: &I, value: &T) -> Result<(), TestCaseError> {
+    let bytes = io
+        .serialize(value)
+        .map_err(|e| TestCaseError::fail(format!("serialize failed: {e}")))?;
+    let decoded = io
+        .deserialize(&bytes)
+        .map_err(|e| TestCaseError::fail(format!("deserialize failed: {e}")))?;
+
+    prop_assert_eq!(&decoded, value);
+
+    Ok(())
+}
+
+/// Encoding is deterministic, and streaming it through
+/// [`Io::serialize_into`] produces the same bytes as [`Io::serialize`].
+pub fn deterministic<T, I: Io<T>>(io: &I, value: &T) -> Result<(), TestCaseError> {
+    let serialize = |value| {
+        io.serialize(value)
+            .map_err(|e| TestCaseError::fail(format!("serialize failed: {e}")))
+    };
+
+    let bytes = serialize(value)?;
+    prop_assert_eq!(&bytes, &serialize(value)?);
+
+    let mut streamed = Vec::new();
+    io.serialize_into(value, &mut streamed)
+        .map_err(|e| TestCaseError::fail(format!("serialize_into failed: {e}")))?;
+    prop_assert_eq!(bytes, streamed);
+
+    Ok(())
+}
+
+/// Truncated encodings of `value` are rejected.
+pub fn rejects_truncated<T: Debug, I: Io<T>>(io: &I, value: &T) -> Result<(), TestCaseError> {
+    let bytes = io
+        .serialize(value)
+        .map_err(|e| TestCaseError::fail(format!("serialize failed: {e}")))?;
+
+    let mut lengths = vec![0, bytes.len() / 2, bytes.len().saturating_sub(1)];
+    lengths.dedup();
+
+    for len in lengths.into_iter().filter(|len| *len < bytes.len()) {
+        let decoded = io.deserialize(&bytes[..len]);
+        prop_assert!(
+            decoded.is_err(),
+            "{len} out of {} bytes decoded into {decoded:?}",
+            bytes.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Encodings of `value` followed by trailing bytes are rejected, both by
+/// [`Io::deserialize`] and by [`Io::deserialize_from`].
+pub fn rejects_trailing<T: Debug, I: Io<T>>(io: &I, value: &T) -> Result<(), TestCaseError> {
+    let bytes = io
+        .serialize(value)
+        .map_err(|e| TestCaseError::fail(format!("serialize failed: {e}")))?;
+
+    for trailing in [&[0][..], &[0xff], &[0; 32]] {
+        let extended = [&bytes[..], trailing].concat();
+
+        let decoded = io.deserialize(&extended);
+        prop_assert!(
+            decoded.is_err(),
+            "{} trailing bytes were ignored, decoded {decoded:?}",
+            trailing.len()
+        );
+
+        let decoded = io.deserialize_from(&mut Bytewise(&extended));
+        prop_assert!(
+            decoded.is_err(),
+            "{} trailing bytes were ignored while streaming, decoded {decoded:?}",
+            trailing.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// Decoding the encoding of `value` through [`Io::deserialize_from`] yields
+/// `value`, even when the reader returns a single byte at a time.
+pub fn streams<T: PartialEq + Debug, I: Io<T>>(io: &I, value: &T) -> Result<(), TestCaseError> {
+    let bytes = io
+        .serialize(value)
+        .map_err(|e| TestCaseError::fail(format!("serialize failed: {e}")))?;
+
+    let decoded = io
+        .deserialize_from(&mut Bytewise(&bytes))
+        .map_err(|e| TestCaseError::fail(format!("deserialize_from failed: {e}")))?;
+    prop_assert_eq!(&decoded, value);
+
+    Ok(())
+}
+
+/// Reader returning at most one byte per read.
+struct Bytewise<'a>(&'a [u8]);
+
+impl Read for Bytewise<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.0.len()).min(1);
+        buf[..len].copy_from_slice(&self.0[..len]);
+        self.0 = &self.0[len..];
+
+        len
+    }
+}
+
+/// Generates a module named `$name` with property tests running the
+/// conformance checks of [`crate::testing`] against codec `$io`, for values
+/// generated by `$strategy`. Checks the codec cannot pass by design - e.g.
+/// `rejects_trailing` for a codec which cannot tell where its encoding ends -
+/// are listed in `unchecked(...)` and skipped.
+#[macro_export]
+macro_rules! io_conformance {
+    ($name: ident, $io: expr, $strategy: expr) => {
+        $crate::io_conformance!($name, $io, $strategy, unchecked());
+    };
+    ($name: ident, $io: expr, $strategy: expr, unchecked($($unchecked: ident),* $(,)?)) => {
+        mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+            use $crate::testing::proptest::prelude::*;
+
+            const UNCHECKED: &[&str] = &[$(stringify!($unchecked)),*];
+
+            $crate::testing::proptest::proptest! {
+                #[test]
+                fn roundtrip(value in $strategy) {
+                    $crate::testing::roundtrip(&$io, &value)?;
+                }
+
+                #[test]
+                fn deterministic(value in $strategy) {
+                    $crate::testing::deterministic(&$io, &value)?;
+                }
+
+                #[test]
+                fn rejects_truncated(value in $strategy) {
+                    if !UNCHECKED.contains(&"rejects_truncated") {
+                        $crate::testing::rejects_truncated(&$io, &value)?;
+                    }
+                }
+
+                #[test]
+                fn rejects_trailing(value in $strategy) {
+                    if !UNCHECKED.contains(&"rejects_trailing") {
+                        $crate::testing::rejects_trailing(&$io, &value)?;
+                    }
+                }
+
+                #[test]
+                fn streams(value in $strategy) {
+                    $crate::testing::streams(&$io, &value)?;
+                }
+            }
+        }
+    };
+}
@@ -0,0 +1,156 @@
+use proptest::prelude::*;
+use univm_io::{io_conformance, raw::RawIo, result::ResultIo, schema::Fingerprinted};
+use univm_io_macros::Schema;
+
+#[derive(Debug, Clone, PartialEq, Schema)]
+#[cfg_attr(feature = "ssz", derive(ssz::Ssz))]
+#[cfg_attr(
+    any(feature = "bincode", feature = "postcard"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+struct Input {
+    a: u64,
+    b: u64,
+}
+
+fn input() -> impl Strategy<Value = Input> {
+    (any::<u64>(), any::<u64>()).prop_map(|(a, b)| Input { a, b })
+}
+
+#[cfg(any(
+    feature = "bincode",
+    feature = "postcard",
+    feature = "borsh",
+    feature = "rkyv"
+))]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    any(feature = "bincode", feature = "postcard"),
+    derive(serde::Serialize, serde::Deserialize)
+)]
+#[cfg_attr(
+    feature = "borsh",
+    derive(borsh::BorshSerialize, borsh::BorshDeserialize)
+)]
+#[cfg_attr(
+    feature = "rkyv",
+    derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)
+)]
+struct Record {
+    id: u32,
+    payload: Vec<u8>,
+    memo: Option<String>,
+}
+
+#[cfg(any(
+    feature = "bincode",
+    feature = "postcard",
+    feature = "borsh",
+    feature = "rkyv"
+))]
+fn record() -> impl Strategy<Value = Record> {
+    (
+        any::<u32>(),
+        proptest::collection::vec(any::<u8>(), 0..256),
+        proptest::option::of(".{0,32}"),
+    )
+        .prop_map(|(id, payload, memo)| Record { id, payload, memo })
+}
+
+/// Test codec for `Input` - two little-endian `u64`s, with no other framing.
+#[derive(Default)]
+struct InputIo;
+
+impl univm_io::Io<Input> for InputIo {
+    type Error = std::array::TryFromSliceError;
+
+    fn serialize_into<W: univm_io::Write + ?Sized>(
+        &self,
+        value: &Input,
+        writer: &mut W,
+    ) -> Result<(), Self::Error> {
+        writer.write(&value.a.to_le_bytes());
+        writer.write(&value.b.to_le_bytes());
+        Ok(())
+    }
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Input, Self::Error> {
+        let bytes: &[u8; 16] = bytes.try_into()?;
+        let (a, b) = bytes.split_at(8);
+
+        Ok(Input {
+            a: u64::from_le_bytes(a.try_into()?),
+            b: u64::from_le_bytes(b.try_into()?),
+        })
+    }
+}
+
+// Every byte string is a valid encoding.
+io_conformance!(
+    raw_io,
+    RawIo,
+    proptest::collection::vec(any::<u8>(), 0..256),
+    unchecked(rejects_truncated, rejects_trailing)
+);
+io_conformance!(
+    result_io,
+    ResultIo(InputIo),
+    prop_oneof![input().prop_map(Ok), input().prop_map(Err)]
+);
+io_conformance!(fingerprinted_io, Fingerprinted(InputIo), input());
+
+#[cfg(feature = "ssz")]
+io_conformance!(ssz_io, univm_io::ssz::SszIo, input());
+
+#[cfg(feature = "bincode")]
+io_conformance!(bincode_io, univm_io::bincode::BincodeIo, record());
+
+#[cfg(feature = "postcard")]
+io_conformance!(postcard_io, univm_io::postcard::PostcardIo, record());
+
+#[cfg(feature = "borsh")]
+io_conformance!(borsh_io, univm_io::borsh::BorshIo, record());
+
+#[cfg(feature = "rkyv")]
+io_conformance!(rkyv_io, univm_io::rkyv::RkyvIo, record());
+
+#[cfg(feature = "compression")]
+io_conformance!(
+    compressed_io,
+    univm_io::compressed::Compressed(InputIo),
+    input()
+);
+
+#[cfg(feature = "abi")]
+mod abi {
+    use alloy_sol_types::sol;
+
+    use super::*;
+
+    sol! {
+        #[derive(Debug, PartialEq)]
+        struct Output {
+            uint64 sum;
+            bytes memo;
+        }
+    }
+
+    fn output() -> impl Strategy<Value = Output> {
+        (any::<u64>(), proptest::collection::vec(any::<u8>(), 0..128)).prop_map(|(sum, memo)| {
+            Output {
+                sum,
+                memo: memo.into(),
+            }
+        })
+    }
+
+    io_conformance!(abi_io, univm_io::abi::AbiIo, output());
+}
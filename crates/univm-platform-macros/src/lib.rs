@@ -1,23 +1,96 @@
 use proc_macro::TokenStream;
 use quote::quote;
 use syn::{
-    Ident, ItemFn, ReturnType, parse::Parse, parse_macro_input, parse_quote, spanned::Spanned,
+    Ident, ItemFn, ReturnType, parse::Parse, parse_macro_input, parse_quote,
+    punctuated::Punctuated, spanned::Spanned,
 };
 
 struct EntrypointAttributes {
-    io: syn::Ident,
+    input_io: syn::Type,
+    output_io: syn::Type,
+
+    /// Type the host encodes the input from, for functions taking the input by
+    /// reference.
+    owned: Option<syn::Type>,
 }
 
 impl Parse for EntrypointAttributes {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        let io = input.parse::<syn::Ident>().map_err(|e| {
-            syn::Error::new(
-                e.span(),
-                "Entrypoint requires at least one attribute - IO kind",
-            )
-        })?;
+        if input.is_empty() {
+            return Err(input.error("Entrypoint requires at least one attribute - IO kind"));
+        }
+
+        // Single codec, used for both directions.
+        let mut io = None;
+        if !(input.peek(syn::Ident) && input.peek2(syn::Token![=])) {
+            io = Some(input.parse::<syn::Type>()?);
+
+            if !input.is_empty() {
+                input.parse::<syn::Token![,]>()?;
+            }
+        }
+
+        let mut input_io = None;
+        let mut output_io = None;
+        let mut owned = None;
+
+        let args = Punctuated::<TypeArgument, syn::Token![,]>::parse_terminated(input)?;
+        for arg in args {
+            let slot = match arg.name.to_string().as_str() {
+                "input" | "output" if io.is_some() => {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        "Codec already given for both directions",
+                    ));
+                }
+                "input" => &mut input_io,
+                "output" => &mut output_io,
+                "owned" => &mut owned,
+                _ => {
+                    return Err(syn::Error::new(
+                        arg.name.span(),
+                        "Unknown argument, expected `input`, `output` or `owned`",
+                    ));
+                }
+            };
+
+            if slot.replace(arg.ty).is_some() {
+                return Err(syn::Error::new(
+                    arg.name.span(),
+                    format!("Duplicate `{}` argument", arg.name),
+                ));
+            }
+        }
 
-        Ok(EntrypointAttributes { io })
+        match (io, input_io, output_io) {
+            (Some(io), None, None) => Ok(EntrypointAttributes {
+                input_io: io.clone(),
+                output_io: io,
+                owned,
+            }),
+            (None, Some(input_io), Some(output_io)) => Ok(EntrypointAttributes {
+                input_io,
+                output_io,
+                owned,
+            }),
+            _ => Err(input.error("Entrypoint requires both `input` and `output` codecs")),
+        }
+    }
+}
+
+/// `name = Type` argument of the entrypoint attribute.
+struct TypeArgument {
+    name: Ident,
+    ty: syn::Type,
+}
+
+impl Parse for TypeArgument {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let ty = input.parse()?;
+
+        Ok(TypeArgument { name, ty })
     }
 }
 
@@ -50,12 +123,33 @@ fn emit_entrypoint(
         }
     };
 
+    if let Some(owned) = &attr.owned
+        && !matches!(input.as_ref(), syn::Type::Reference(_))
+    {
+        return Err(syn::Error::new(
+            owned.span(),
+            "`owned` only applies to functions taking the input by reference",
+        ));
+    }
+
     let output = match item.sig.output {
         ReturnType::Default => parse_quote!(()),
         ReturnType::Type(_, ref t) => t.clone(),
     };
 
-    let io = attr.io;
+    let bindings = emit_bindings(&attr, input, &output, &item.sig.ident);
+
+    // Codecs are constructed with `Default`.
+    let input_io = &attr.input_io;
+    let input_io = quote!(<#input_io as ::core::default::Default>::default());
+    let output_io = &attr.output_io;
+    let output_io = quote!(<#output_io as ::core::default::Default>::default());
+
+    let commit = if is_result(&output) {
+        quote! { univm_platform::commit_result::<UniVMCurrentPlatform, _, _, _>(#output_io, &output); }
+    } else {
+        quote! { univm_platform::commit::<UniVMCurrentPlatform, #output>(#output_io, &output); }
+    };
 
     let fn_body = &item.block;
     let fn_vis = &item.vis;
@@ -64,10 +158,7 @@ fn emit_entrypoint(
     let cloned_sig = {
         let mut s = item.sig.clone();
 
-        s.ident = Ident::new(
-            format!("__univm_{}", s.ident.to_string()).as_str(),
-            s.span(),
-        );
+        s.ident = Ident::new(format!("__univm_{}", s.ident).as_str(), s.span());
 
         s
     };
@@ -75,9 +166,28 @@ fn emit_entrypoint(
     let fn_name = item.sig.ident;
     let cloned_ident = &cloned_sig.ident;
 
-    let result = quote! {
-        include!(concat!(env!("CARGO_MANIFEST_DIR"), "/.univm/platform.rs"));
+    // Inputs taken by reference are accessed in place, without deserializing.
+    let call = match input.as_ref() {
+        syn::Type::Reference(reference) => {
+            let view = &reference.elem;
 
+            quote! {
+                let output = univm_platform::read_view::<UniVMCurrentPlatform, #view, _, _>(
+                    #input_io,
+                    |input| #cloned_ident(input),
+                );
+            }
+        }
+        _ => quote! {
+            let input = univm_platform::read::<UniVMCurrentPlatform, #input>(#input_io);
+
+            let output = #cloned_ident(input);
+        },
+    };
+
+    // The platform is included in the function body, as a crate may define
+    // several functions.
+    let result = quote! {
         #[cfg(target_os = "zkvm")]
         #(#fn_attrs)*
         #cloned_sig {
@@ -86,17 +196,21 @@ fn emit_entrypoint(
 
         #[cfg(target_os = "zkvm")]
         #fn_vis fn #fn_name() {
-            let input = univm_platform::read::<UniVMCurrentPlatform, #input>(#io);
+            include!(concat!(env!("CARGO_MANIFEST_DIR"), "/.univm/platform.rs"));
 
-            let output = #cloned_ident(input);
+            univm_platform::init::<UniVMCurrentPlatform>();
+
+            #call
 
-            univm_platform::commit::<UniVMCurrentPlatform, #output>(#io, output);
+            #commit
         }
 
         #[cfg(not(target_os = "zkvm"))]
         #fn_vis fn #fn_name() {
             panic!("Not implemented - cannot call zkvm function from");
         }
+
+        #bindings
     };
 
     println!("debug: {result}");
@@ -104,6 +218,64 @@ fn emit_entrypoint(
     Ok(result)
 }
 
+/// Module with the types of the function as seen by the host - `__Input`,
+/// `__Output`, `__InputIo` and `__OutputIo` - which `univm-build` generates
+/// the program bindings from. Names are prefixed not to shadow the types of
+/// the function. `__Input` is left out for functions taking the input by
+/// reference, unless the `owned` type is given.
+fn emit_bindings(
+    attr: &EntrypointAttributes,
+    input: &syn::Type,
+    output: &syn::Type,
+    fn_name: &Ident,
+) -> proc_macro2::TokenStream {
+    let module = Ident::new(&format!("__univm_bindings_{fn_name}"), fn_name.span());
+
+    let input = match (input, &attr.owned) {
+        (_, Some(owned)) => Some(owned),
+        (syn::Type::Reference(_), None) => None,
+        (input, None) => Some(input),
+    }
+    .map(|input| quote! { pub type __Input = #input; });
+
+    let input_io = &attr.input_io;
+    let output_io = &attr.output_io;
+    let output_io = if is_result(output) {
+        quote!(univm_platform::__ResultIo<#output_io>)
+    } else {
+        quote!(#output_io)
+    };
+
+    quote! {
+        #[doc(hidden)]
+        #[allow(unused_imports)]
+        pub mod #module {
+            use super::*;
+
+            #input
+            pub type __Output = #output;
+            pub type __InputIo = #input_io;
+            pub type __OutputIo = #output_io;
+        }
+    }
+}
+
+/// Whether the function returns `Result<T, E>`, in which case both outcomes
+/// are committed.
+fn is_result(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.path.segments.last().is_some_and(|segment| {
+        segment.ident == "Result"
+            && matches!(
+                &segment.arguments,
+                syn::PathArguments::AngleBracketed(args) if args.args.len() == 2
+            )
+    })
+}
+
 #[proc_macro_attribute]
 pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
     let attr = parse_macro_input!(attr as EntrypointAttributes);
@@ -116,3 +288,12 @@ pub fn function(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     stream.into()
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn build_tests() {
+        let t = trybuild::TestCases::new();
+        t.compile_fail("tests/fail/*.rs");
+    }
+}
@@ -0,0 +1,8 @@
+struct Input;
+
+#[univm_platform_macros::function(RkyvIo, owned = Input)]
+pub fn by_value(input: Input) -> u64 {
+    0
+}
+
+fn main() {}
@@ -1,23 +1,90 @@
-use risc0_zkvm::guest::env;
-use univm_platform::Platform;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use risc0_zkvm::guest::env::{self, FdWriter, Write as _};
+use risc0_zkvm_platform::{fileno, syscall::sys_read};
+use univm_io::Read;
+use univm_platform::{Platform, aligned::AlignedBuffer, log, panic};
+
+/// File descriptors carrying guest log records and panic reports, must match
+/// the ones read by `univm-risc0` on the host.
+const LOG_FD: u32 = 0x5556_4d01;
+const PANIC_FD: u32 = 0x5556_4d02;
 
 pub struct Risc0Platform;
 
+/// Input written by `univm-risc0` - big-endian `u32` length, followed by the
+/// bytes, which are read from the host as requested.
+pub struct Risc0Input {
+    remaining: usize,
+}
+
+impl Read for Risc0Input {
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        let len = buf.len().min(self.remaining);
+        if len > 0 {
+            env::read_slice(&mut buf[..len]);
+            self.remaining -= len;
+        }
+
+        len
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) {
+        let len = buf.len();
+        buf.resize(len + self.remaining, 0);
+        self.read(&mut buf[len..]);
+    }
+}
+
 impl Platform for Risc0Platform {
-    fn read_input() -> Vec<u8> {
+    fn read_selector() -> u32 {
+        let mut selector = [0u8; 4];
+        env::read_slice(&mut selector);
+
+        u32::from_le_bytes(selector)
+    }
+
+    type Input = Risc0Input;
+
+    fn input() -> Risc0Input {
         let mut len = [0u8; 4];
         env::read_slice(&mut len);
 
-        let len = u32::from_be_bytes(len) as usize;
-        let mut buffer = vec![0u8; len];
-        env::read_slice(&mut buffer);
+        Risc0Input {
+            remaining: u32::from_be_bytes(len) as usize,
+        }
+    }
 
-        buffer
+    fn read_input_aligned() -> AlignedBuffer {
+        let len = Self::input().remaining;
+
+        // `sys_read` copies whole words straight into aligned buffers.
+        //
+        // SAFETY: the read fills all `len` bytes, or panics.
+        unsafe {
+            AlignedBuffer::new_with(len, |dst| {
+                let read = sys_read(fileno::STDIN, dst, len);
+                assert_eq!(read, len, "input ended {} bytes early", len - read);
+            })
+        }
     }
 
     fn write_output(bytes: &[u8]) {
         env::commit_slice(bytes);
     }
+
+    fn write_log(level: log::Level, message: &str) {
+        FdWriter::new(LOG_FD, |_| {}).write_slice(&log::encode_record(level, message));
+    }
+
+    fn report_panic(message: &str, location: &str) {
+        let report = panic::encode_report(message, location, env::cycle_count());
+        FdWriter::new(PANIC_FD, |_| {}).write_slice(&report);
+    }
 }
 
 pub use risc0_zkvm::entry as __univm_entrypoint;
@@ -1,16 +1,135 @@
-use sp1_zkvm::io;
-use univm_platform::Platform;
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use sp1_zkvm::{
+    io,
+    syscalls::{syscall_hint_len, syscall_hint_read},
+};
+use univm_io::Read;
+use univm_platform::{Platform, aligned::AlignedBuffer, log, panic};
+
+/// File descriptors carrying guest log records and panic reports, must match
+/// the hooks registered by `univm-sp1` on the host.
+const LOG_FD: u32 = 0x5556_4d01;
+const PANIC_FD: u32 = 0x5556_4d02;
 
 pub struct Sp1Platform;
 
+/// Input written by `univm-sp1` - big-endian `u32` length, followed by the
+/// bytes split into chunks. Chunks are read from the host one at a time, and
+/// dropped once consumed.
+pub struct Sp1Input {
+    remaining: usize,
+    chunk: Vec<u8>,
+    offset: usize,
+}
+
+impl Sp1Input {
+    /// Unread bytes of the current chunk, reading the next one if needed.
+    /// Empty only once the input is consumed.
+    fn chunk(&mut self) -> &[u8] {
+        if self.offset == self.chunk.len() && self.remaining > 0 {
+            self.chunk = io::read_vec();
+            self.offset = 0;
+
+            // An empty chunk would never make progress.
+            assert!(
+                !self.chunk.is_empty(),
+                "input ended {} bytes early",
+                self.remaining
+            );
+        }
+
+        let len = (self.chunk.len() - self.offset).min(self.remaining);
+        &self.chunk[self.offset..self.offset + len]
+    }
+
+    fn consume(&mut self, len: usize) {
+        self.offset += len;
+        self.remaining -= len;
+    }
+}
+
+impl Read for Sp1Input {
+    fn read(&mut self, buf: &mut [u8]) -> usize {
+        let chunk = self.chunk();
+        let len = buf.len().min(chunk.len());
+        buf[..len].copy_from_slice(&chunk[..len]);
+        self.consume(len);
+
+        len
+    }
+
+    fn read_to_end(&mut self, buf: &mut Vec<u8>) {
+        buf.reserve(self.remaining);
+
+        while self.remaining > 0 {
+            let chunk = self.chunk();
+            let len = chunk.len();
+            buf.extend_from_slice(chunk);
+            self.consume(len);
+        }
+    }
+}
+
 impl Platform for Sp1Platform {
-    fn read_input() -> Vec<u8> {
-        io::read_vec()
+    fn read_selector() -> u32 {
+        let selector = io::read_vec();
+
+        u32::from_le_bytes(selector.try_into().expect("malformed function selector"))
+    }
+
+    type Input = Sp1Input;
+
+    fn input() -> Sp1Input {
+        let len = io::read_vec();
+        let len = u32::from_be_bytes(len.try_into().expect("malformed input length"));
+
+        Sp1Input {
+            remaining: len as usize,
+            chunk: Vec::new(),
+            offset: 0,
+        }
+    }
+
+    fn read_input_aligned() -> AlignedBuffer {
+        let len = Self::input().remaining;
+
+        // Chunks are read from the host straight into the buffer. All but the
+        // last are whole words, keeping the next one word-aligned.
+        //
+        // SAFETY: chunks fill the `len` bytes back to back, the host writing
+        // at most up to the next word past the last one.
+        unsafe {
+            AlignedBuffer::new_with(len, |dst| {
+                let mut filled = 0;
+                while filled < len {
+                    let chunk = syscall_hint_len();
+                    assert!(chunk > 0 && chunk <= len - filled, "malformed input chunk");
+
+                    syscall_hint_read(dst.add(filled), chunk);
+                    filled += chunk;
+                }
+            })
+        }
     }
 
     fn write_output(bytes: &[u8]) {
         io::commit_slice(bytes);
     }
+
+    fn write_log(level: log::Level, message: &str) {
+        io::write(LOG_FD, &log::encode_record(level, message));
+    }
+
+    fn report_panic(message: &str, location: &str) {
+        // The guest has no access to the cycle counter - the host fills it in
+        // when receiving the report.
+        io::write(PANIC_FD, &panic::encode_report(message, location, 0));
+    }
 }
 
 pub use sp1_zkvm::entrypoint as __univm_entrypoint;
@@ -0,0 +1,104 @@
+//! Owned buffers for guest input, aligned so that codecs can reinterpret them
+//! in place.
+
+use alloc::{vec, vec::Vec};
+use core::{
+    mem::MaybeUninit,
+    ops::{Deref, DerefMut},
+    ptr, slice,
+};
+
+/// Alignment of [`AlignedBuffer`] contents - covers word-aligned reads on
+/// every backend, as well as codecs with stricter requirements, e.g. rkyv.
+pub const ALIGN: usize = 16;
+
+#[derive(Clone, Copy)]
+#[repr(C, align(16))]
+struct Block([MaybeUninit<u8>; ALIGN]);
+
+/// Owned byte buffer, aligned to [`ALIGN`] bytes.
+pub struct AlignedBuffer {
+    blocks: Vec<Block>,
+    len: usize,
+}
+
+impl AlignedBuffer {
+    /// Zero-filled buffer of `len` bytes.
+    pub fn zeroed(len: usize) -> Self {
+        Self {
+            blocks: vec![Block([MaybeUninit::new(0); ALIGN]); len.div_ceil(ALIGN)],
+            len,
+        }
+    }
+
+    /// Buffer of `len` bytes, written by `fill` through a pointer to its
+    /// uninitialized contents. Saves zero-filling buffers about to be
+    /// overwritten, e.g. by the host.
+    ///
+    /// # Safety
+    ///
+    /// `fill` must initialize the first `len` bytes, and may write up to the
+    /// next multiple of [`ALIGN`].
+    pub unsafe fn new_with(len: usize, fill: impl FnOnce(*mut u8)) -> Self {
+        let blocks_len = len.div_ceil(ALIGN);
+        let mut blocks = Vec::<Block>::with_capacity(blocks_len);
+        fill(blocks.as_mut_ptr().cast());
+
+        // SAFETY: the capacity is `blocks_len`, and blocks may be uninitialized.
+        unsafe { blocks.set_len(blocks_len) };
+
+        Self { blocks, len }
+    }
+
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        // SAFETY: copies exactly `bytes.len()` bytes.
+        unsafe {
+            Self::new_with(bytes.len(), |dst| {
+                ptr::copy_nonoverlapping(bytes.as_ptr(), dst, bytes.len())
+            })
+        }
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        // SAFETY: the first `len` bytes of `blocks` are initialized, and `u8` has
+        // no alignment requirements.
+        unsafe { slice::from_raw_parts(self.blocks.as_ptr().cast(), self.len) }
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: as above.
+        unsafe { slice::from_raw_parts_mut(self.blocks.as_mut_ptr().cast(), self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aligned_contents() {
+        for len in [0, 1, 15, 16, 17, 100] {
+            let bytes = (0..len as u8).collect::<Vec<_>>();
+            let buffer = AlignedBuffer::from_slice(&bytes);
+
+            assert_eq!(&*buffer, &bytes[..]);
+            assert_eq!(buffer.as_ptr().align_offset(ALIGN), 0);
+        }
+    }
+
+    #[test]
+    fn filled_in_place() {
+        // SAFETY: writes all 20 bytes, and the padding up to 32.
+        let buffer =
+            unsafe { AlignedBuffer::new_with(20, |dst| ptr::write_bytes(dst, 7, 2 * ALIGN)) };
+
+        assert_eq!(&*buffer, &[7; 20]);
+        assert_eq!(AlignedBuffer::zeroed(20).len(), 20);
+    }
+}
@@ -0,0 +1,414 @@
+//! 256-bit unsigned integers with modular arithmetic.
+//!
+//! Modular multiplication - and the reduction of operands not below the
+//! modulus - is routed to the bigint accelerator of the zkVM the guest is
+//! compiled for (`sys_bigint` on risc0, `uint256_mulmod` on sp1), and falls
+//! back to plain Rust everywhere else. Both accelerators treat a zero modulus
+//! as `2^256`, which is used to implement wrapping multiplication.
+
+use core::cmp::Ordering;
+
+use cfg_zkvm::cfg_zkvm;
+
+/// Number of 32-bit limbs in [`U256`].
+pub const LIMBS: usize = 8;
+
+/// 256-bit unsigned integer, stored as little-endian 32-bit limbs.
+///
+/// The layout matches the one expected by the zkVM bigint syscalls, so values
+/// are passed to them without conversion.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(C, align(4))]
+pub struct U256([u32; LIMBS]);
+
+impl U256 {
+    pub const ZERO: Self = Self([0; LIMBS]);
+    pub const ONE: Self = Self([1, 0, 0, 0, 0, 0, 0, 0]);
+    pub const MAX: Self = Self([u32::MAX; LIMBS]);
+
+    pub const fn from_limbs(limbs: [u32; LIMBS]) -> Self {
+        Self(limbs)
+    }
+
+    pub const fn as_limbs(&self) -> &[u32; LIMBS] {
+        &self.0
+    }
+
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0u32; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.rchunks_exact(4)) {
+            *limb = u32::from_be_bytes(chunk.try_into().unwrap());
+        }
+
+        Self(limbs)
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.rchunks_exact_mut(4).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_be_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn from_le_bytes(bytes: [u8; 32]) -> Self {
+        let mut limbs = [0u32; LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(4)) {
+            *limb = u32::from_le_bytes(chunk.try_into().unwrap());
+        }
+
+        Self(limbs)
+    }
+
+    pub fn to_le_bytes(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        for (chunk, limb) in bytes.chunks_exact_mut(4).zip(self.0.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+
+        bytes
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|limb| *limb == 0)
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        (self.0[index / 32] >> (index % 32)) & 1 == 1
+    }
+
+    pub fn overflowing_add(self, rhs: Self) -> (Self, bool) {
+        let mut result = [0u32; LIMBS];
+        let mut carry = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (sum, c1) = self.0[i].overflowing_add(rhs.0[i]);
+            let (sum, c2) = sum.overflowing_add(carry as u32);
+            *limb = sum;
+            carry = c1 || c2;
+        }
+
+        (Self(result), carry)
+    }
+
+    pub fn overflowing_sub(self, rhs: Self) -> (Self, bool) {
+        let mut result = [0u32; LIMBS];
+        let mut borrow = false;
+        for (i, limb) in result.iter_mut().enumerate() {
+            let (diff, b1) = self.0[i].overflowing_sub(rhs.0[i]);
+            let (diff, b2) = diff.overflowing_sub(borrow as u32);
+            *limb = diff;
+            borrow = b1 || b2;
+        }
+
+        (Self(result), borrow)
+    }
+
+    pub fn checked_add(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (value, false) => Some(value),
+            (_, true) => None,
+        }
+    }
+
+    pub fn checked_sub(self, rhs: Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (value, false) => Some(value),
+            (_, true) => None,
+        }
+    }
+
+    pub fn wrapping_add(self, rhs: Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(self, rhs: Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+
+    pub fn wrapping_mul(self, rhs: Self) -> Self {
+        mul_mod(&self.0, &rhs.0, &Self::ZERO.0).into()
+    }
+
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let (low, high) = mul_wide(&self, &rhs);
+
+        high.is_zero().then_some(low)
+    }
+
+    /// Computes `self mod modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn reduce(self, modulus: Self) -> Self {
+        assert!(!modulus.is_zero(), "modulus must not be zero");
+
+        if self < modulus {
+            return self;
+        }
+
+        // Multiplying by one reduces with a single accelerator call.
+        mul_mod(&self.0, &Self::ONE.0, &modulus.0).into()
+    }
+
+    /// Computes `(self + rhs) mod modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn add_mod(self, rhs: Self, modulus: Self) -> Self {
+        let (lhs, rhs) = (self.reduce(modulus), rhs.reduce(modulus));
+
+        match lhs.overflowing_add(rhs) {
+            (sum, true) => sum.wrapping_sub(modulus),
+            (sum, false) if sum >= modulus => sum.wrapping_sub(modulus),
+            (sum, false) => sum,
+        }
+    }
+
+    /// Computes `(self - rhs) mod modulus`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero.
+    pub fn sub_mod(self, rhs: Self, modulus: Self) -> Self {
+        let (lhs, rhs) = (self.reduce(modulus), rhs.reduce(modulus));
+
+        match lhs.overflowing_sub(rhs) {
+            (diff, true) => diff.wrapping_add(modulus),
+            (diff, false) => diff,
+        }
+    }
+
+    /// Computes `(self * rhs) mod modulus`, using the zkVM accelerator when
+    /// available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus` is zero - use [`U256::wrapping_mul`] to multiply
+    /// modulo `2^256`.
+    pub fn mul_mod(self, rhs: Self, modulus: Self) -> Self {
+        let (lhs, rhs) = (self.reduce(modulus), rhs.reduce(modulus));
+
+        mul_mod(&lhs.0, &rhs.0, &modulus.0).into()
+    }
+}
+
+impl From<[u32; LIMBS]> for U256 {
+    fn from(limbs: [u32; LIMBS]) -> Self {
+        Self(limbs)
+    }
+}
+
+impl From<u64> for U256 {
+    fn from(value: u64) -> Self {
+        Self::from(value as u128)
+    }
+}
+
+impl From<u128> for U256 {
+    fn from(value: u128) -> Self {
+        let mut limbs = [0u32; LIMBS];
+        for (i, limb) in limbs.iter_mut().take(4).enumerate() {
+            *limb = (value >> (32 * i)) as u32;
+        }
+
+        Self(limbs)
+    }
+}
+
+impl PartialOrd for U256 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U256 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.iter().rev().cmp(other.0.iter().rev())
+    }
+}
+
+/// Schoolbook multiplication, returning the low and high halves of the
+/// 512-bit product.
+fn mul_wide(lhs: &U256, rhs: &U256) -> (U256, U256) {
+    let mut product = [0u32; 2 * LIMBS];
+    for i in 0..LIMBS {
+        let mut carry = 0u64;
+        for j in 0..LIMBS {
+            let value = product[i + j] as u64 + (lhs.0[i] as u64) * (rhs.0[j] as u64) + carry;
+            product[i + j] = value as u32;
+            carry = value >> 32;
+        }
+        product[i + LIMBS] = carry as u32;
+    }
+
+    let (low, high) = product.split_at(LIMBS);
+
+    (
+        U256(low.try_into().unwrap()),
+        U256(high.try_into().unwrap()),
+    )
+}
+
+/// Reduces the 512-bit value `high * 2^256 + low` modulo non-zero `modulus`,
+/// one bit at a time. The high half is skipped when zero.
+fn reduce_wide(low: &U256, high: &U256, modulus: &U256) -> U256 {
+    let mut remainder = U256::ZERO;
+    let bits = if high.is_zero() {
+        LIMBS * 32
+    } else {
+        2 * LIMBS * 32
+    };
+
+    for index in (0..bits).rev() {
+        let bit = if index >= LIMBS * 32 {
+            high.bit(index - LIMBS * 32)
+        } else {
+            low.bit(index)
+        };
+
+        let (doubled, carry) = remainder.overflowing_add(remainder);
+        remainder = doubled;
+        if carry || remainder >= *modulus {
+            remainder = remainder.wrapping_sub(*modulus);
+        }
+
+        if bit {
+            let (incremented, carry) = remainder.overflowing_add(U256::ONE);
+            remainder = incremented;
+            if carry || remainder >= *modulus {
+                remainder = remainder.wrapping_sub(*modulus);
+            }
+        }
+    }
+
+    remainder
+}
+
+#[cfg_zkvm(risc0)]
+fn mul_mod(x: &[u32; LIMBS], y: &[u32; LIMBS], modulus: &[u32; LIMBS]) -> [u32; LIMBS] {
+    use risc0_zkvm_platform::syscall::{bigint::OP_MULTIPLY, sys_bigint};
+
+    let mut result = [0u32; LIMBS];
+    // SAFETY: all pointers come from references to word-aligned arrays.
+    unsafe { sys_bigint(&mut result, OP_MULTIPLY, x, y, modulus) };
+
+    result
+}
+
+#[cfg_zkvm(sp1)]
+fn mul_mod(x: &[u32; LIMBS], y: &[u32; LIMBS], modulus: &[u32; LIMBS]) -> [u32; LIMBS] {
+    use sp1_zkvm::syscalls::syscall_uint256_mulmod;
+
+    // The syscall expects the modulus to be placed right after `y` and writes
+    // the result into `x`.
+    let mut result = *x;
+    let mut y_and_modulus = [0u32; 2 * LIMBS];
+    y_and_modulus[..LIMBS].copy_from_slice(y);
+    y_and_modulus[LIMBS..].copy_from_slice(modulus);
+
+    syscall_uint256_mulmod(&mut result, y_and_modulus.as_ptr() as *const [u32; LIMBS]);
+
+    result
+}
+
+#[cfg_zkvm(not(any(risc0, sp1)))]
+fn mul_mod(x: &[u32; LIMBS], y: &[u32; LIMBS], modulus: &[u32; LIMBS]) -> [u32; LIMBS] {
+    let (low, high) = mul_wide(&U256(*x), &U256(*y));
+    let modulus = U256(*modulus);
+
+    if modulus.is_zero() {
+        return low.0;
+    }
+
+    reduce_wide(&low, &high, &modulus).0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bigint::U256;
+
+    fn u(value: u128) -> U256 {
+        U256::from(value)
+    }
+
+    #[test]
+    fn byte_conversions_roundtrip() {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+
+        let value = U256::from_be_bytes(bytes);
+        assert_eq!(value.to_be_bytes(), bytes);
+        assert_eq!(value.as_limbs()[0], 0x1c1d1e1f);
+
+        let value = U256::from_le_bytes(bytes);
+        assert_eq!(value.to_le_bytes(), bytes);
+        assert_eq!(value.as_limbs()[0], 0x03020100);
+    }
+
+    #[test]
+    fn checked_arithmetic_detects_overflow() {
+        assert_eq!(u(1).checked_add(u(2)), Some(u(3)));
+        assert_eq!(U256::MAX.checked_add(U256::ONE), None);
+        assert_eq!(U256::ZERO.checked_sub(U256::ONE), None);
+        assert_eq!(
+            u(1 << 64).checked_mul(u(1 << 64)),
+            Some(U256::from_limbs([0, 0, 0, 0, 1, 0, 0, 0]))
+        );
+        assert_eq!(U256::MAX.checked_mul(u(2)), None);
+        assert_eq!(
+            U256::MAX.wrapping_mul(u(2)),
+            U256::MAX.wrapping_sub(U256::ONE)
+        );
+    }
+
+    #[test]
+    fn modular_arithmetic_matches_native_integers() {
+        let modulus = u(0xffff_ffff_ffff_ffc5);
+        let values = [
+            0u128,
+            1,
+            7,
+            0xffff_ffff_ffff_ffc4,
+            0xdead_beef_cafe_babe,
+            u64::MAX as u128,
+        ];
+
+        for a in values {
+            for b in values {
+                let m = 0xffff_ffff_ffff_ffc5u128;
+                assert_eq!(u(a).mul_mod(u(b), modulus), u((a % m) * (b % m) % m));
+                assert_eq!(u(a).add_mod(u(b), modulus), u((a % m + b % m) % m));
+                assert_eq!(u(a).sub_mod(u(b), modulus), u((a % m + m - b % m) % m));
+            }
+        }
+    }
+
+    #[test]
+    fn mul_mod_reduces_full_width_products() {
+        let modulus = U256::MAX;
+
+        assert_eq!(U256::MAX.mul_mod(U256::MAX, modulus), U256::ZERO);
+        assert_eq!(
+            U256::MAX.wrapping_sub(U256::ONE).mul_mod(u(2), modulus),
+            U256::MAX.wrapping_sub(u(2))
+        );
+    }
+
+    #[test]
+    fn reduces_operands_above_modulus() {
+        let modulus = U256::MAX.wrapping_sub(U256::ONE);
+        assert_eq!(U256::MAX.reduce(modulus), U256::ONE);
+        assert_eq!(modulus.reduce(modulus), U256::ZERO);
+        assert_eq!(U256::MAX.add_mod(U256::MAX, modulus), u(2));
+        assert_eq!(U256::ONE.sub_mod(U256::MAX, modulus), U256::ZERO);
+
+        let modulus = u(1).wrapping_add(U256::from(u128::MAX));
+        assert_eq!(U256::MAX.reduce(modulus), U256::from(u128::MAX));
+        assert_eq!(U256::MAX.mul_mod(U256::MAX, modulus), U256::ONE);
+    }
+}
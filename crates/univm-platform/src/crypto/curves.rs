@@ -0,0 +1,331 @@
+//! Elliptic-curve operations routed to the accelerated implementation of the
+//! zkVM the guest is compiled for.
+//!
+//! On `risc0` and `sp1` the vendor-patched crates are used, which replace the
+//! field and group arithmetic with precompile calls. Every other target
+//! (including the host) uses the upstream pure Rust crates.
+
+use alloc::vec::Vec;
+
+use cfg_zkvm::cfg_zkvm;
+use thiserror::Error;
+
+#[cfg_zkvm(risc0)]
+use {
+    bls12_381_risc0 as bls12_381, ed25519_dalek_risc0 as ed25519_dalek, k256_risc0 as k256,
+    substrate_bn_risc0 as bn,
+};
+
+#[cfg_zkvm(sp1)]
+use {
+    bls12_381_sp1 as bls12_381, ed25519_dalek_sp1 as ed25519_dalek, k256_sp1 as k256,
+    substrate_bn_sp1 as bn,
+};
+
+#[cfg_zkvm(not(any(risc0, sp1)))]
+use substrate_bn as bn;
+
+/// Size of a single `(G1, G2)` pair in the bn254 pairing check input.
+pub const BN254_PAIR_SIZE: usize = 192;
+
+#[derive(Debug, Error)]
+pub enum CurveError {
+    #[error("invalid recovery id {0}")]
+    InvalidRecoveryId(u8),
+
+    #[error("invalid signature")]
+    InvalidSignature,
+
+    #[error("invalid public key")]
+    InvalidPublicKey,
+
+    #[error("invalid field element")]
+    InvalidFieldElement,
+
+    #[error("point is not on the curve or not in the correct subgroup")]
+    InvalidPoint,
+
+    #[error("pairing input length {0} is not a multiple of {BN254_PAIR_SIZE}")]
+    InvalidPairingInput(usize),
+}
+
+/// Recovers the secp256k1 public key which produced `signature` over
+/// `message_hash`.
+///
+/// Returns the key in SEC1 uncompressed form (`0x04 || x || y`).
+pub fn secp256k1_ecrecover(
+    message_hash: &[u8; 32],
+    signature: &[u8; 64],
+    recovery_id: u8,
+) -> Result<[u8; 65], CurveError> {
+    use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+
+    let recovery_id =
+        RecoveryId::from_byte(recovery_id).ok_or(CurveError::InvalidRecoveryId(recovery_id))?;
+    let signature = Signature::from_slice(signature).map_err(|_| CurveError::InvalidSignature)?;
+
+    let key = VerifyingKey::recover_from_prehash(message_hash, &signature, recovery_id)
+        .map_err(|_| CurveError::InvalidSignature)?;
+
+    let mut result = [0u8; 65];
+    result.copy_from_slice(key.to_encoded_point(false).as_bytes());
+
+    Ok(result)
+}
+
+/// Verifies an ed25519 `signature` of `message`.
+pub fn ed25519_verify(
+    public_key: &[u8; 32],
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<(), CurveError> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let key = VerifyingKey::from_bytes(public_key).map_err(|_| CurveError::InvalidPublicKey)?;
+    let signature = Signature::from_bytes(signature);
+
+    key.verify(message, &signature)
+        .map_err(|_| CurveError::InvalidSignature)
+}
+
+/// Performs the bn254 pairing check, using the same input encoding as the
+/// `0x08` Ethereum precompile (EIP-197): a sequence of 192-byte pairs of
+/// uncompressed G1 and G2 points.
+///
+/// Returns `true` when the product of all pairings equals one.
+pub fn bn254_pairing_check(input: &[u8]) -> Result<bool, CurveError> {
+    use bn::{AffineG1, AffineG2, Fq, Fq2, G1, G2, Group, Gt};
+
+    if !input.len().is_multiple_of(BN254_PAIR_SIZE) {
+        return Err(CurveError::InvalidPairingInput(input.len()));
+    }
+
+    let fq = |bytes: &[u8]| Fq::from_slice(bytes).map_err(|_| CurveError::InvalidFieldElement);
+
+    let pairs = input
+        .chunks_exact(BN254_PAIR_SIZE)
+        .map(|pair| {
+            let (x, y) = (fq(&pair[0..32])?, fq(&pair[32..64])?);
+            let g1 = if x.is_zero() && y.is_zero() {
+                G1::zero()
+            } else {
+                AffineG1::new(x, y)
+                    .map_err(|_| CurveError::InvalidPoint)?
+                    .into()
+            };
+
+            // Extension field elements are encoded with the imaginary part first.
+            let x = Fq2::new(fq(&pair[96..128])?, fq(&pair[64..96])?);
+            let y = Fq2::new(fq(&pair[160..192])?, fq(&pair[128..160])?);
+            let g2 = if x.is_zero() && y.is_zero() {
+                G2::zero()
+            } else {
+                AffineG2::new(x, y)
+                    .map_err(|_| CurveError::InvalidPoint)?
+                    .into()
+            };
+
+            Ok((g1, g2))
+        })
+        .collect::<Result<Vec<(G1, G2)>, CurveError>>()?;
+
+    Ok(bn::pairing_batch(&pairs) == Gt::one())
+}
+
+/// Performs the BLS12-381 pairing check over compressed `(G1, G2)` points.
+///
+/// Returns `true` when the product of all pairings equals the identity.
+pub fn bls12_381_pairing_check(pairs: &[([u8; 48], [u8; 96])]) -> Result<bool, CurveError> {
+    use bls12_381::{G1Affine, G2Affine, G2Prepared, Gt, multi_miller_loop};
+
+    let points = pairs
+        .iter()
+        .map(|(g1, g2)| {
+            let g1 = Option::<G1Affine>::from(G1Affine::from_compressed(g1))
+                .ok_or(CurveError::InvalidPoint)?;
+            let g2 = Option::<G2Affine>::from(G2Affine::from_compressed(g2))
+                .ok_or(CurveError::InvalidPoint)?;
+
+            Ok((g1, G2Prepared::from(g2)))
+        })
+        .collect::<Result<Vec<_>, CurveError>>()?;
+
+    let terms = points.iter().map(|(g1, g2)| (g1, g2)).collect::<Vec<_>>();
+
+    Ok(multi_miller_loop(&terms).final_exponentiation() == Gt::identity())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex<const N: usize>(hex: &str) -> [u8; N] {
+        let mut bytes = [0u8; N];
+        assert_eq!(hex.len(), 2 * N);
+
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+
+        bytes
+    }
+
+    #[test]
+    fn ecrecover_recovers_transaction_signer() {
+        // Signed transaction of the EIP-155 example (chain id 1, `v = 37`),
+        // sent from 0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f.
+        let hash = hex("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53");
+        let signature = hex(concat!(
+            "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+            "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        ));
+        let key: [u8; 65] = hex(concat!(
+            "04",
+            "4bc2a31265153f07e70e0bab08724e6b85e217f8cd628ceb62974247bb493382",
+            "ce28cab79ad7119ee1ad3ebcdb98a16805211530ecc6cfefa1b88e6dff99232a",
+        ));
+
+        assert_eq!(secp256k1_ecrecover(&hash, &signature, 0).unwrap(), key);
+        assert_ne!(secp256k1_ecrecover(&hash, &signature, 1).unwrap(), key);
+        assert!(matches!(
+            secp256k1_ecrecover(&hash, &signature, 4),
+            Err(CurveError::InvalidRecoveryId(4))
+        ));
+        assert!(matches!(
+            secp256k1_ecrecover(&hash, &[0; 64], 0),
+            Err(CurveError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn ed25519_verifies_rfc8032_vectors() {
+        // Tests 1 to 3 of RFC 8032, section 7.1.
+        let vectors: [(&str, &[u8], &str); 3] = [
+            (
+                "d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a",
+                &[],
+                concat!(
+                    "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e06522490155",
+                    "5fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+                ),
+            ),
+            (
+                "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+                &[0x72],
+                concat!(
+                    "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da",
+                    "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+                ),
+            ),
+            (
+                "fc51cd8e6218a1a38da47ed00230f0580816ed13ba3303ac5deb911548908025",
+                &[0xaf, 0x82],
+                concat!(
+                    "6291d657deec24024827e69c3abe01a30ce548a284743a445e3680d7db5ac3ac",
+                    "18ff9b538d16f290ae67f760984dc6594a7c15e9716ed28dc027beceea1ec40a",
+                ),
+            ),
+        ];
+
+        for (public_key, message, signature) in vectors {
+            let public_key = hex(public_key);
+            let signature = hex(signature);
+
+            assert!(ed25519_verify(&public_key, message, &signature).is_ok());
+            assert!(matches!(
+                ed25519_verify(&public_key, b"other message", &signature),
+                Err(CurveError::InvalidSignature)
+            ));
+        }
+    }
+
+    /// Generator of G1, `(1, 2)`.
+    fn bn254_g1() -> [u8; 64] {
+        let mut point = [0u8; 64];
+        point[31] = 1;
+        point[63] = 2;
+        point
+    }
+
+    /// Generator of G2 given in EIP-197, imaginary parts first.
+    fn bn254_g2() -> [u8; 128] {
+        hex(concat!(
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2",
+            "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed",
+            "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b",
+            "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa",
+        ))
+    }
+
+    fn bn254_pairs(g1s: &[[u8; 64]]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for g1 in g1s {
+            input.extend_from_slice(g1);
+            input.extend_from_slice(&bn254_g2());
+        }
+
+        input
+    }
+
+    #[test]
+    fn bn254_pairing_check_follows_eip197() {
+        // -G1 is `(1, p - 2)`.
+        let mut negated = bn254_g1();
+        negated[32..].copy_from_slice(&hex::<32>(
+            "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45",
+        ));
+
+        assert!(bn254_pairing_check(&[]).unwrap());
+        assert!(bn254_pairing_check(&bn254_pairs(&[bn254_g1(), negated])).unwrap());
+        assert!(!bn254_pairing_check(&bn254_pairs(&[bn254_g1()])).unwrap());
+        assert!(!bn254_pairing_check(&bn254_pairs(&[bn254_g1(), bn254_g1()])).unwrap());
+
+        // The point at infinity is encoded as zeros and pairs to one.
+        assert!(bn254_pairing_check(&bn254_pairs(&[[0; 64]])).unwrap());
+    }
+
+    #[test]
+    fn bn254_pairing_check_rejects_invalid_input() {
+        let input = bn254_pairs(&[bn254_g1()]);
+        assert!(matches!(
+            bn254_pairing_check(&input[..BN254_PAIR_SIZE - 1]),
+            Err(CurveError::InvalidPairingInput(191))
+        ));
+
+        // `(1, 3)` is not on the curve.
+        let mut input = input;
+        input[63] = 3;
+        assert!(matches!(
+            bn254_pairing_check(&input),
+            Err(CurveError::InvalidPoint)
+        ));
+
+        // Coordinates must be below the field modulus.
+        input[32..64].fill(0xff);
+        assert!(matches!(
+            bn254_pairing_check(&input),
+            Err(CurveError::InvalidFieldElement)
+        ));
+    }
+
+    #[test]
+    fn bls12_381_pairing_check_cancels_negated_point() {
+        use bls12_381::{G1Affine, G2Affine};
+
+        let p = G1Affine::generator();
+        let q = G2Affine::generator().to_compressed();
+        let pair = (p.to_compressed(), q);
+        let negated = ((-p).to_compressed(), q);
+
+        assert!(bls12_381_pairing_check(&[pair, negated]).unwrap());
+        assert!(!bls12_381_pairing_check(&[pair]).unwrap());
+        assert!(!bls12_381_pairing_check(&[pair, pair]).unwrap());
+
+        let mut invalid = pair;
+        invalid.0[47] ^= 1;
+        assert!(matches!(
+            bls12_381_pairing_check(&[invalid]),
+            Err(CurveError::InvalidPoint)
+        ));
+    }
+}
@@ -1,31 +1,251 @@
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
 pub use cfg_zkvm::cfg_zkvm;
-use univm_io::Io;
+use univm_io::{Io, Read, View, result::ResultIo};
 pub use univm_platform_macros::function;
 
+use crate::aligned::AlignedBuffer;
+
+pub mod aligned;
+pub mod bigint;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+pub mod log;
+pub mod panic;
+
+/// Declares the guest entrypoint, running one of the given functions - the
+/// one selected by the host.
 #[macro_export]
 macro_rules! entrypoint {
-    ($curr: path) => {
+    ($($function: path),+ $(,)?) => {
         include!(concat!(env!("CARGO_MANIFEST_DIR"), "/.univm/platform.rs"));
 
-        __univm_entrypoint!($curr);
+        // Selectors only hash function names, so functions from different
+        // modules may collide.
+        const _: () = $crate::__assert_distinct_selectors(&[$(
+            $crate::__selector(stringify!($function)),
+        )+]);
+
+        fn __univm_main() {
+            $crate::dispatch::<UniVMCurrentPlatform>(&[$(
+                ($crate::__selector(stringify!($function)), $function as fn()),
+            )+]);
+        }
+
+        __univm_entrypoint!(__univm_main);
     };
 }
 
+#[doc(hidden)]
+pub use univm_io::{result::ResultIo as __ResultIo, selector::selector as __selector};
+
+#[doc(hidden)]
+pub const fn __assert_distinct_selectors(selectors: &[u32]) {
+    let mut i = 0;
+    while i < selectors.len() {
+        let mut j = i + 1;
+        while j < selectors.len() {
+            if selectors[i] == selectors[j] {
+                panic!("guest functions passed to `entrypoint!` must have distinct names");
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+}
+
 pub trait Platform {
-    fn read_input() -> Vec<u8>;
+    /// Reads the selector of the function to run, sent by the host ahead of
+    /// the input.
+    fn read_selector() -> u32;
+
+    /// Reader over the input sent by the host, consuming it as it is read.
+    type Input: Read;
+
+    /// Opens the input channel. Called at most once per execution.
+    fn input() -> Self::Input;
+
+    /// Reads the whole input into memory.
+    fn read_input() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        Self::input().read_to_end(&mut bytes);
+
+        bytes
+    }
+
+    /// Reads the whole input into a buffer aligned to [`aligned::ALIGN`]
+    /// bytes. Backends override this to fill the buffer straight from the
+    /// input channel.
+    fn read_input_aligned() -> AlignedBuffer {
+        AlignedBuffer::from_slice(&Self::read_input())
+    }
 
     fn write_output(bytes: &[u8]);
+
+    fn write_log(level: log::Level, message: &str);
+
+    fn report_panic(message: &str, location: &str);
 }
 
+/// Prepares the guest runtime for platform `P` - routes logs and panic
+/// reports to the host. Called by the generated entrypoint.
+///
+/// Without `std` there is no panic hook to install, so panics are handled by
+/// the panic handler of the zkvm runtime alone.
+pub fn init<P: Platform>() {
+    log::init::<P>();
+    #[cfg(feature = "std")]
+    panic::install_hook::<P>();
+}
+
+/// Runs the function whose selector was sent by the host. Guests exposing
+/// several functions commit the selector after its output, so that a claim of
+/// a single-function guest is exactly the encoded output. Called by the
+/// generated entrypoint, with the functions passed to [`entrypoint!`].
+pub fn dispatch<P: Platform>(functions: &[(u32, fn())]) {
+    let selector = P::read_selector();
+
+    let Some((_, function)) = functions
+        .iter()
+        .find(|(candidate, _)| *candidate == selector)
+    else {
+        panic!("no guest function with selector {selector:#010x}");
+    };
+
+    function();
+
+    // Functions share the program id, so the selector is committed for the
+    // host to check which one produced the output.
+    if functions.len() > 1 {
+        P::write_output(&selector.to_le_bytes());
+    }
+}
+
+/// Deserializes the input. Codecs decoding incrementally read it straight from
+/// the input channel, others get an aligned buffer holding the whole input.
 pub fn read<P: Platform, T>(io: impl Io<T>) -> T {
-    let content = P::read_input();
+    fn streaming<T, I: Io<T>>(_: &I) -> bool {
+        I::STREAMING
+    }
+
+    if streaming(&io) {
+        io.deserialize_from(&mut P::input()).unwrap()
+    } else {
+        io.deserialize(&P::read_input_aligned()).unwrap()
+    }
+}
 
-    let result = io.deserialize(&content).unwrap();
+/// Reads the input and passes it to `f` as a view over the input buffer,
+/// without deserializing it.
+pub fn read_view<P: Platform, T: ?Sized, V: View<T>, O>(io: V, f: impl FnOnce(&T) -> O) -> O {
+    assert!(
+        V::ALIGN <= aligned::ALIGN,
+        "unsupported input alignment {}",
+        V::ALIGN
+    );
 
-    result
+    let content = P::read_input_aligned();
+
+    f(io.view(&content).unwrap())
+}
+
+/// Writes serialized bytes straight to the output of platform `P`.
+pub struct OutputWriter<P>(PhantomData<P>);
+
+impl<P> Default for OutputWriter<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
 }
 
-pub fn commit<P: Platform, T>(io: impl Io<T>, value: T) {
-    let bytes = io.serialize(value).unwrap();
-    P::write_output(&bytes);
+impl<P: Platform> univm_io::Write for OutputWriter<P> {
+    fn write(&mut self, bytes: &[u8]) {
+        P::write_output(bytes);
+    }
+}
+
+pub fn commit<P: Platform, T>(io: impl Io<T>, value: &T) {
+    io.serialize_into(value, &mut OutputWriter::<P>::default())
+        .unwrap();
+}
+
+/// Commits either outcome of a fallible function, tagged as described in
+/// [`ResultIo`], so that both can be proven.
+pub fn commit_result<P: Platform, T, E, I: Io<T> + Io<E>>(io: I, value: &Result<T, E>) {
+    commit::<P, Result<T, E>>(ResultIo(io), value);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::{Cell, RefCell};
+
+    use super::*;
+
+    thread_local! {
+        static SELECTOR: Cell<u32> = const { Cell::new(0) };
+        static OUTPUT: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+    }
+
+    struct MockPlatform;
+
+    impl Platform for MockPlatform {
+        fn read_selector() -> u32 {
+            SELECTOR.get()
+        }
+
+        type Input = &'static [u8];
+
+        fn input() -> &'static [u8] {
+            &[]
+        }
+
+        fn write_output(bytes: &[u8]) {
+            OUTPUT.with_borrow_mut(|output| output.extend_from_slice(bytes));
+        }
+
+        fn write_log(_level: log::Level, _message: &str) {}
+
+        fn report_panic(_message: &str, _location: &str) {}
+    }
+
+    fn first() {
+        MockPlatform::write_output(b"first");
+    }
+
+    fn second() {
+        MockPlatform::write_output(b"second");
+    }
+
+    fn run(selector: u32, functions: &[(u32, fn())]) -> Vec<u8> {
+        SELECTOR.set(selector);
+        dispatch::<MockPlatform>(functions);
+
+        OUTPUT.take()
+    }
+
+    #[test]
+    fn commits_selector_after_output() {
+        assert_eq!(run(1, &[(1, first as fn())]), b"first");
+        assert_eq!(
+            run(2, &[(1, first as fn()), (2, second as fn())]),
+            b"second\x02\0\0\0"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "no guest function with selector 0x00000003")]
+    fn rejects_unknown_selector() {
+        run(3, &[(1, first as fn()), (2, second as fn())]);
+    }
+
+    #[test]
+    #[should_panic(expected = "must have distinct names")]
+    fn rejects_duplicate_selectors() {
+        __assert_distinct_selectors(&[__selector("a::run"), __selector("b::run")]);
+    }
 }
@@ -0,0 +1,150 @@
+//! Leveled logging from guest programs.
+//!
+//! Log records are handed to the current [`Platform`], which forwards them to
+//! the host, where they are collected into the execution report. Records above
+//! [`STATIC_MAX_LEVEL`] are removed at compile time (see the `max-level-*`
+//! features), the rest can be filtered at runtime with [`set_max_level`].
+
+use alloc::string::ToString;
+use core::{
+    fmt, ptr,
+    sync::atomic::{AtomicPtr, AtomicU8, Ordering},
+};
+
+pub use univm_io::log::{Level, encode_record};
+
+use crate::Platform;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum LevelFilter {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LevelFilter {
+    fn from_u8(value: u8) -> Self {
+        match value {
+            0 => Self::Off,
+            1 => Self::Error,
+            2 => Self::Warn,
+            3 => Self::Info,
+            _ => Self::Debug,
+        }
+    }
+}
+
+impl PartialEq<LevelFilter> for Level {
+    fn eq(&self, other: &LevelFilter) -> bool {
+        *self as u8 == *other as u8
+    }
+}
+
+impl PartialOrd<LevelFilter> for Level {
+    fn partial_cmp(&self, other: &LevelFilter) -> Option<core::cmp::Ordering> {
+        (*self as u8).partial_cmp(&(*other as u8))
+    }
+}
+
+/// Most verbose level compiled into the guest.
+pub const STATIC_MAX_LEVEL: LevelFilter = if cfg!(feature = "max-level-off") {
+    LevelFilter::Off
+} else if cfg!(feature = "max-level-error") {
+    LevelFilter::Error
+} else if cfg!(feature = "max-level-warn") {
+    LevelFilter::Warn
+} else if cfg!(feature = "max-level-info") {
+    LevelFilter::Info
+} else {
+    LevelFilter::Debug
+};
+
+static MAX_LEVEL: AtomicU8 = AtomicU8::new(LevelFilter::Debug as u8);
+
+/// `fn(Level, &str)` records are routed to, or null before [`init`]. Stored
+/// as a pointer, as `OnceLock` is not available without `std`.
+static LOGGER: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Routes log records to platform `P`. Called by the generated entrypoint.
+pub fn init<P: Platform>() {
+    let logger: fn(Level, &str) = P::write_log;
+    let _ = LOGGER.compare_exchange(
+        ptr::null_mut(),
+        logger as *mut (),
+        Ordering::AcqRel,
+        Ordering::Acquire,
+    );
+}
+
+fn logger() -> Option<fn(Level, &str)> {
+    let logger = LOGGER.load(Ordering::Acquire);
+
+    // SAFETY: non-null values are only ever stored by `init`, from a
+    // `fn(Level, &str)`.
+    (!logger.is_null()).then(|| unsafe { core::mem::transmute::<*mut (), fn(Level, &str)>(logger) })
+}
+
+pub fn set_max_level(level: LevelFilter) {
+    MAX_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn max_level() -> LevelFilter {
+    LevelFilter::from_u8(MAX_LEVEL.load(Ordering::Relaxed))
+}
+
+#[doc(hidden)]
+pub fn __log(level: Level, args: fmt::Arguments) {
+    if level > max_level() {
+        return;
+    }
+
+    if let Some(logger) = logger() {
+        match args.as_str() {
+            Some(message) => logger(level, message),
+            None => logger(level, &args.to_string()),
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! log {
+    ($level: expr, $($arg: tt)+) => {{
+        let level = $level;
+        if level <= $crate::log::STATIC_MAX_LEVEL {
+            $crate::log::__log(level, format_args!($($arg)+));
+        }
+    }};
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg: tt)+) => {
+        $crate::log!($crate::log::Level::Error, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg: tt)+) => {
+        $crate::log!($crate::log::Level::Warn, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg: tt)+) => {
+        $crate::log!($crate::log::Level::Info, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg: tt)+) => {
+        $crate::log!($crate::log::Level::Debug, $($arg)+)
+    };
+}
+
+pub use crate::{debug, error, info, warn};
@@ -0,0 +1,39 @@
+//! Reporting of guest panics to the host.
+
+#[cfg(feature = "std")]
+use std::panic::{self, PanicHookInfo};
+
+pub use univm_io::panic::encode_report;
+
+#[cfg(feature = "std")]
+use crate::Platform;
+
+/// Installs a panic hook reporting the panic message and location through
+/// platform `P`, before running the previously installed hook. Called by the
+/// generated entrypoint.
+#[cfg(feature = "std")]
+pub fn install_hook<P: Platform>() {
+    let previous = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        let location = info
+            .location()
+            .map(|location| location.to_string())
+            .unwrap_or_else(|| "<unknown>".to_owned());
+
+        P::report_panic(payload_message(info), &location);
+
+        previous(info);
+    }));
+}
+
+#[cfg(feature = "std")]
+fn payload_message<'a>(info: &'a PanicHookInfo) -> &'a str {
+    if let Some(message) = info.payload().downcast_ref::<&str>() {
+        message
+    } else if let Some(message) = info.payload().downcast_ref::<String>() {
+        message
+    } else {
+        "Box<dyn Any>"
+    }
+}
@@ -1,13 +1,34 @@
-use std::path::Path;
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
 
 use risc0_build::GuestOptions;
-use univm_interface::compiler::{CompilationResult, Compiler};
+use univm_interface::compiler::{Artifact, CompilationResult, Compiler, toolchain_version};
 
 #[derive(Default)]
 pub struct Risc0Compiler;
 
+/// Profile guests are built with, as picked by `risc0-build`.
+const PROFILE_VAR: &str = "RISC0_BUILD_DEBUG";
+
 impl Compiler for Risc0Compiler {
-    fn compile(&self, crate_path: &Path, target_path: &Path) -> Result<CompilationResult, ()> {
+    fn id(&self) -> String {
+        let profile = match env::var(PROFILE_VAR).as_deref() {
+            Ok("1") => "debug",
+            _ => "release",
+        };
+
+        format!("risc0-{profile}")
+    }
+
+    fn build_inputs(&self) -> Vec<String> {
+        vec![format!("toolchain={}", toolchain_version("risc0"))]
+    }
+
+    fn build(&self, crate_path: &Path, target_path: &Path) -> Result<Artifact, ()> {
+        println!("cargo:rerun-if-env-changed={PROFILE_VAR}");
+
         let package = risc0_build::get_package(crate_path);
 
         let entries =
@@ -19,22 +40,30 @@ impl Compiler for Risc0Compiler {
 
         assert!(entries.len() == 1, "too many entries");
 
-        let elf_path = &entry.path;
-        let image_id = entry.image_id.as_words();
+        Ok(Artifact {
+            elf_path: PathBuf::from(entry.path.as_ref()),
+            program_id: entry.image_id.as_words().to_vec(),
+        })
+    }
+
+    fn emit(&self, artifact: &Artifact) -> Result<CompilationResult, ()> {
+        // Reused artifacts are keyed by the profile, so changing it must rerun
+        // the build script even when the guest was not built.
+        println!("cargo:rerun-if-env-changed={PROFILE_VAR}");
+
+        let elf_path = &artifact.elf_path;
+        let image_id = &artifact.program_id;
 
         Ok(CompilationResult {
             vm_name: "Risc0".to_owned(),
             vm_full_name: "univm_risc0::Risc0".to_owned(),
             program_name: "[<$base_program_name Risc0>]".to_owned(),
             program_impl: format!(
-                r#"pub struct [<$base_program_name Risc0>](univm_risc0::Risc0Program<$input, $output, $io>);
+                r#"pub struct [<$base_program_name Risc0>](univm_risc0::Risc0Program<$input, $output, $input_io, $output_io>);
 
                 impl [<$base_program_name Risc0>] {{
                     fn init(_vm: &univm_risc0::Risc0) -> Self {{
-                        const ELF: &[u8] = include_bytes!({elf_path:?});
-                        const DIGEST: [u32; 8] = {image_id:?};
-
-                        Self(univm_risc0::Risc0Program::<$input, $output, $io>::new(ELF, DIGEST, $io))
+                        Self(univm_risc0::Risc0Program::<$input, $output, $input_io, $output_io>::new(__UNIVM_RISC0_ELF, __UNIVM_RISC0_IMAGE_ID, $base_program_name::SELECTOR, $base_program_name::COMMITS_SELECTOR, <$input_io>::default(), <$output_io>::default()))
                     }}
                 }}
 
@@ -42,25 +71,35 @@ impl Compiler for Risc0Compiler {
                     type Input = $input;
                     type Output = $output;
 
-                    fn execute(&self, zkvm: &univm_risc0::Risc0, input: Self::Input) -> Result<(Self::Output, univm_risc0::Risc0ExecutionReport), ()> {{
+                    fn execute(&self, zkvm: &univm_risc0::Risc0, input: &Self::Input) -> Result<(Self::Output, univm_risc0::Risc0ExecutionReport), univm_interface::Error> {{
                         self.0.execute(zkvm, input)
                     }}
 
-                    fn prove(&self, zkvm: &univm_risc0::Risc0, input: Self::Input) -> Result<(Self::Output, univm_risc0::Risc0Proof, univm_risc0::Risc0ExecutionReport), ()> {{
+                    fn prove(&self, zkvm: &univm_risc0::Risc0, input: &Self::Input) -> Result<(Self::Output, univm_risc0::Risc0Proof, univm_risc0::Risc0ProvingReport), univm_interface::Error> {{
                         self.0.prove(zkvm, input)
                     }}
 
-                    fn verify(&self, zkvm: &univm_risc0::Risc0, proof: &univm_risc0::Risc0Proof) -> bool {{
+                    fn verify(&self, zkvm: &univm_risc0::Risc0, proof: &univm_risc0::Risc0Proof) -> Result<Self::Output, univm_interface::Error> {{
                         self.0.verify(zkvm, proof)
                     }}
                 }}"#
             ),
+            shared_items: format!(
+                r#"static __UNIVM_RISC0_ELF: &[u8] = include_bytes!({elf_path:?});
+                const __UNIVM_RISC0_IMAGE_ID: [u32; 8] = {image_id:?};"#
+            ),
         })
     }
 
     fn emit_platform(&self) -> Result<String, ()> {
         Ok(PLATFORM_CODE.to_owned())
     }
+
+    fn program_id(&self, elf: &[u8]) -> Option<Vec<u32>> {
+        let image_id = risc0_zkvm::compute_image_id(elf).expect("invalid guest ELF");
+
+        Some(image_id.as_words().to_vec())
+    }
 }
 
 const PLATFORM_CODE: &'static str = r#"
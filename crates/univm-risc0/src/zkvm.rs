@@ -1,15 +1,36 @@
-use std::{marker::PhantomData, rc::Rc};
+use std::{marker::PhantomData, rc::Rc, sync::Arc};
 
 use risc0_zkvm::{
     Digest, Executor, ExecutorEnv, ProveInfo, Prover, Receipt, SessionInfo, default_executor,
     default_prover,
 };
-use univm_interface::{ExecutionReport, GuestProgram, Proof, ProvingReport, Zkvm, ZkvmMethods};
+use univm_interface::{
+    Error, ExecutionReport, GuestProgram, LogCollector, LogRecord, OutputCallback, OutputCapture,
+    OutputStream, PanicCollector, Proof, ProvingReport, Zkvm, ZkvmMethods, strip_selector,
+};
 use univm_io::Io;
 
+/// File descriptors carrying guest log records and panic reports, must match
+/// the ones written by `univm-platform-risc0`.
+const LOG_FD: u32 = 0x5556_4d01;
+const PANIC_FD: u32 = 0x5556_4d02;
+
 pub struct Risc0 {
     executor: Rc<dyn Executor + 'static>,
     prover: Rc<dyn Prover + 'static>,
+    output_callback: Option<OutputCallback>,
+}
+
+impl Risc0 {
+    /// Streams guest stdout and stderr to `callback` while executing, in
+    /// addition to capturing them in the execution report.
+    pub fn with_output_callback(
+        mut self,
+        callback: impl Fn(OutputStream, &[u8]) + Send + Sync + 'static,
+    ) -> Self {
+        self.output_callback = Some(Arc::new(callback));
+        self
+    }
 }
 
 impl Default for Risc0 {
@@ -17,6 +38,7 @@ impl Default for Risc0 {
         Self {
             executor: default_executor(),
             prover: default_prover(),
+            output_callback: None,
         }
     }
 }
@@ -41,11 +63,28 @@ impl Proof for Risc0Proof {
     }
 }
 
-pub struct Risc0ExecutionReport(SessionInfo);
+pub struct Risc0ExecutionReport {
+    info: SessionInfo,
+    logs: Vec<LogRecord>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
 
 impl ExecutionReport for Risc0ExecutionReport {
     fn cycles(&self) -> u64 {
-        self.0.cycles()
+        self.info.cycles()
+    }
+
+    fn logs(&self) -> &[LogRecord] {
+        &self.logs
+    }
+
+    fn stdout(&self) -> &[u8] {
+        &self.stdout
+    }
+
+    fn stderr(&self) -> &[u8] {
+        &self.stderr
     }
 }
 
@@ -53,27 +92,45 @@ pub struct Risc0ProvingReport(ProveInfo);
 
 impl ProvingReport for Risc0ProvingReport {}
 
-pub struct Risc0Program<In, Out, TIo: Io<In> + Io<Out>> {
+pub struct Risc0Program<In, Out, InIo: Io<In>, OutIo: Io<Out>> {
     elf: Vec<u8>,
     image_id: Digest,
-    io: TIo,
+    selector: u32,
+    committed_selector: Option<u32>,
+    input_io: InIo,
+    output_io: OutIo,
 
     _phantom: PhantomData<(In, Out)>,
 }
 
-impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> Risc0Program<TInput, TOutput, TIo> {
-    pub fn new(elf: &[u8], image_id: [u32; 8], io: TIo) -> Self {
+impl<TInput, TOutput, InIo: Io<TInput>, OutIo: Io<TOutput>>
+    Risc0Program<TInput, TOutput, InIo, OutIo>
+{
+    /// Program running the guest function with `selector` out of the ones
+    /// exposed by `elf`. Guests exposing several functions commit the
+    /// selector after the output, as told by `commits_selector`.
+    pub fn new(
+        elf: &[u8],
+        image_id: [u32; 8],
+        selector: u32,
+        commits_selector: bool,
+        input_io: InIo,
+        output_io: OutIo,
+    ) -> Self {
         Self {
             elf: elf.to_vec(),
             image_id: image_id.into(),
-            io,
+            selector,
+            committed_selector: commits_selector.then_some(selector),
+            input_io,
+            output_io,
             _phantom: PhantomData,
         }
     }
 }
 
-impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> GuestProgram<Risc0>
-    for Risc0Program<TInput, TOutput, TIo>
+impl<TInput, TOutput, InIo: Io<TInput>, OutIo: Io<TOutput>> GuestProgram<Risc0>
+    for Risc0Program<TInput, TOutput, InIo, OutIo>
 {
     type Input = TInput;
     type Output = TOutput;
@@ -81,20 +138,43 @@ impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> GuestProgram<Risc0>
     fn execute(
         &self,
         zkvm: &Risc0,
-        input: Self::Input,
-    ) -> Result<(Self::Output, Risc0ExecutionReport), ()> {
-        let bytes = self.io.serialize(input).unwrap();
+        input: &Self::Input,
+    ) -> Result<(Self::Output, Risc0ExecutionReport), Error> {
+        let bytes = self.input_io.serialize(input).map_err(Error::serialize)?;
         let len: u32 = bytes.len() as u32;
+        let logs = LogCollector::default();
+        let panics = PanicCollector::default();
+        let stdout = OutputCapture::new(OutputStream::Stdout, zkvm.output_callback.clone());
+        let stderr = OutputCapture::new(OutputStream::Stderr, zkvm.output_callback.clone());
         let env = ExecutorEnv::builder()
+            .write_slice(&self.selector.to_le_bytes())
             .write_slice(&len.to_be_bytes())
             .write_slice(&bytes)
+            .write_fd(LOG_FD, logs.clone())
+            .write_fd(PANIC_FD, panics.clone())
+            .stdout(stdout.clone())
+            .stderr(stderr.clone())
             .build()
-            .unwrap();
-
-        let info = zkvm.executor.execute(env, &self.elf).unwrap();
-
-        let output = <TIo as Io<Self::Output>>::deserialize(&self.io, &info.journal.bytes).unwrap();
-        let report = Risc0ExecutionReport(info);
+            .map_err(Error::backend)?;
+
+        let info = zkvm
+            .executor
+            .execute(env, &self.elf)
+            .map_err(|err| panics.into_error(err))?;
+
+        let output = self
+            .output_io
+            .deserialize(strip_selector(
+                self.committed_selector,
+                &info.journal.bytes,
+            )?)
+            .map_err(Error::deserialize)?;
+        let report = Risc0ExecutionReport {
+            info,
+            logs: logs.take(),
+            stdout: stdout.take(),
+            stderr: stderr.take(),
+        };
 
         Ok((output, report))
     }
@@ -102,24 +182,47 @@ impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> GuestProgram<Risc0>
     fn prove(
         &self,
         zkvm: &Risc0,
-        input: Self::Input,
-    ) -> Result<(Self::Output, Risc0Proof, Risc0ProvingReport), ()> {
-        let bytes = self.io.serialize(input).unwrap();
-        let env = ExecutorEnv::builder().write_slice(&bytes).build().unwrap();
-
-        let info = zkvm.prover.prove(env, &self.elf).unwrap();
-
-        let output =
-            <TIo as Io<Self::Output>>::deserialize(&self.io, &info.receipt.journal.bytes).unwrap();
+        input: &Self::Input,
+    ) -> Result<(Self::Output, Risc0Proof, Risc0ProvingReport), Error> {
+        let bytes = self.input_io.serialize(input).map_err(Error::serialize)?;
+        let len: u32 = bytes.len() as u32;
+        let panics = PanicCollector::default();
+        let env = ExecutorEnv::builder()
+            .write_slice(&self.selector.to_le_bytes())
+            .write_slice(&len.to_be_bytes())
+            .write_slice(&bytes)
+            // Logs are only collected by `execute`.
+            .write_fd(LOG_FD, std::io::sink())
+            .write_fd(PANIC_FD, panics.clone())
+            .build()
+            .map_err(Error::backend)?;
+
+        let info = zkvm
+            .prover
+            .prove(env, &self.elf)
+            .map_err(|err| panics.into_error(err))?;
+
+        let output = self
+            .output_io
+            .deserialize(strip_selector(
+                self.committed_selector,
+                &info.receipt.journal.bytes,
+            )?)
+            .map_err(Error::deserialize)?;
         let proof = Risc0Proof(info.receipt.clone());
         let report = Risc0ProvingReport(info);
 
         Ok((output, proof, report))
     }
 
-    fn verify(&self, _zkvm: &Risc0, proof: &Risc0Proof) -> Result<Self::Output, ()> {
-        proof.0.verify(self.image_id).map_err(|_| ())?;
+    fn verify(&self, _zkvm: &Risc0, proof: &Risc0Proof) -> Result<Self::Output, Error> {
+        proof.0.verify(self.image_id).map_err(Error::verification)?;
 
-        self.io.deserialize(&proof.0.journal.bytes).map_err(|_| ())
+        self.output_io
+            .deserialize(strip_selector(
+                self.committed_selector,
+                &proof.0.journal.bytes,
+            )?)
+            .map_err(Error::deserialize)
     }
 }
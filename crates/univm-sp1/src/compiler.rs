@@ -1,13 +1,51 @@
 use std::{fs::File, io::Read, path::Path};
 
 use sp1_helper::BuildArgs;
-use univm_interface::compiler::{CompilationResult, Compiler};
+use univm_interface::compiler::{Artifact, CompilationResult, Compiler, toolchain_version};
 
 #[derive(Default)]
-pub struct Sp1Compiler;
+pub struct Sp1Compiler {
+    /// Arguments guests are built with, besides their package and output
+    /// directory.
+    args: BuildArgs,
+}
+
+impl Sp1Compiler {
+    pub fn new(args: BuildArgs) -> Self {
+        Self { args }
+    }
+}
 
 impl Compiler for Sp1Compiler {
-    fn compile(&self, crate_path: &Path, target_path: &Path) -> Result<CompilationResult, ()> {
+    fn id(&self) -> String {
+        "sp1".to_owned()
+    }
+
+    fn build_inputs(&self) -> Vec<String> {
+        let args = &self.args;
+
+        // The tag covers the SP1 version too, which it defaults to. Docker
+        // builds use the toolchain of the image.
+        vec![
+            format!("docker={}", args.docker),
+            format!("tag={}", args.tag),
+            format!("features={:?}", args.features),
+            format!("rustflags={:?}", args.rustflags),
+            format!("no_default_features={}", args.no_default_features),
+            format!("ignore_rust_version={}", args.ignore_rust_version),
+            format!("binaries={:?}", args.binaries),
+            format!(
+                "toolchain={}",
+                if args.docker {
+                    String::new()
+                } else {
+                    toolchain_version("succinct")
+                }
+            ),
+        ]
+    }
+
+    fn build(&self, crate_path: &Path, target_path: &Path) -> Result<Artifact, ()> {
         let crate_name = {
             let mut file = File::open(crate_path.join("Cargo.toml")).unwrap();
             let mut buffer = String::new();
@@ -26,22 +64,32 @@ impl Compiler for Sp1Compiler {
             BuildArgs {
                 output_directory: Some(target_path.to_str().unwrap().to_owned()),
                 packages: vec![crate_name.clone()],
-                ..Default::default()
+                ..self.args.clone()
             },
         );
 
+        // The ELF is copied to the output directory, named after the crate.
+        // Its path is embedded directly, as `SP1_ELF_*` variables are only set
+        // when the guest is built.
+        Ok(Artifact {
+            elf_path: target_path.join(crate_name),
+            program_id: Vec::new(),
+        })
+    }
+
+    fn emit(&self, artifact: &Artifact) -> Result<CompilationResult, ()> {
+        let elf_path = &artifact.elf_path;
+
         Ok(CompilationResult {
             vm_name: "Sp1".to_owned(),
             vm_full_name: "univm_sp1::Sp1".to_owned(),
             program_name: "[<$base_program_name Sp1>]".to_owned(),
             program_impl: format!(
-                r#"pub struct [<$base_program_name Sp1>](univm_sp1::Sp1Program<$input, $output, $io>);
+                r#"pub struct [<$base_program_name Sp1>](univm_sp1::Sp1Program<$input, $output, $input_io, $output_io>);
 
                 impl [<$base_program_name Sp1>] {{
                     fn init(vm: &univm_sp1::Sp1) -> Self {{
-                        const ELF: &[u8] = include_bytes!(env!("SP1_ELF_{crate_name}"));
-
-                        Self(univm_sp1::Sp1Program::<$input, $output, $io>::new(vm, ELF, $io))
+                        Self(univm_sp1::Sp1Program::<$input, $output, $input_io, $output_io>::new(vm, __UNIVM_SP1_ELF, $base_program_name::SELECTOR, $base_program_name::COMMITS_SELECTOR, <$input_io>::default(), <$output_io>::default()))
                     }}
                 }}
 
@@ -49,19 +97,22 @@ impl Compiler for Sp1Compiler {
                     type Input = $input;
                     type Output = $output;
 
-                    fn execute(&self, zkvm: &univm_sp1::Sp1, input: Self::Input) -> Result<(Self::Output, univm_sp1::Sp1ExecutionReport), ()> {{
+                    fn execute(&self, zkvm: &univm_sp1::Sp1, input: &Self::Input) -> Result<(Self::Output, univm_sp1::Sp1ExecutionReport), univm_interface::Error> {{
                         self.0.execute(zkvm, input)
                     }}
 
-                    fn prove(&self, zkvm: &univm_sp1::Sp1, input: Self::Input) -> Result<(Self::Output, univm_sp1::Sp1Proof, univm_sp1::Sp1ExecutionReport), ()> {{
+                    fn prove(&self, zkvm: &univm_sp1::Sp1, input: &Self::Input) -> Result<(Self::Output, univm_sp1::Sp1Proof, univm_sp1::Sp1ProvingReport), univm_interface::Error> {{
                         self.0.prove(zkvm, input)
                     }}
 
-                    fn verify(&self, zkvm: &univm_sp1::Sp1, proof: &univm_sp1::Sp1Proof) -> bool {{
+                    fn verify(&self, zkvm: &univm_sp1::Sp1, proof: &univm_sp1::Sp1Proof) -> Result<Self::Output, univm_interface::Error> {{
                         self.0.verify(zkvm, proof)
                     }}
                 }}"#
             ),
+            shared_items: format!(
+                r#"static __UNIVM_SP1_ELF: &[u8] = include_bytes!({elf_path:?});"#
+            ),
         })
     }
 
@@ -1,3 +1,4 @@
+pub use sp1_helper::BuildArgs;
 use univm_interface::compiler::Compiler;
 
 mod zkvm;
@@ -8,3 +9,9 @@ mod compiler;
 pub fn compiler() -> impl Compiler {
     compiler::Sp1Compiler::default()
 }
+
+/// Compiler building guests with `args`, e.g. in docker for reproducible
+/// builds.
+pub fn compiler_with_args(args: BuildArgs) -> impl Compiler {
+    compiler::Sp1Compiler::new(args)
+}
@@ -1,18 +1,73 @@
-use std::marker::PhantomData;
+use std::{marker::PhantomData, sync::Arc};
 
+use sp1_core_executor::hook_fn;
 use sp1_prover::components::CpuProverComponents;
-use sp1_sdk::{Prover, ProverClient, SP1ProvingKey, SP1Stdin, SP1VerifyingKey};
-use univm_interface::{ExecutionReport, GuestProgram, Proof, ProvingReport, Zkvm, ZkvmMethods};
+use sp1_sdk::{
+    Prover, ProverClient, SP1Context, SP1ProvingKey, SP1PublicValues, SP1Stdin, SP1VerifyingKey,
+};
+use univm_interface::{
+    Error, ExecutionReport, GuestPanic, GuestProgram, LogCollector, LogRecord, OutputCallback,
+    OutputCapture, OutputStream, PanicCollector, Proof, ProvingReport, Zkvm, ZkvmMethods,
+    strip_selector,
+};
 use univm_io::Io;
 
+/// File descriptors carrying guest log records and panic reports, must match
+/// the ones written by `univm-platform-sp1`.
+const LOG_FD: u32 = 0x5556_4d01;
+const PANIC_FD: u32 = 0x5556_4d02;
+
+/// Size of the chunks guest input is split into, which `univm-platform-sp1`
+/// reads one at a time.
+const INPUT_CHUNK: usize = 64 * 1024;
+
+/// Writes guest input - `selector` of the function to run (little-endian
+/// `u32`), big-endian `u32` length of `bytes`, followed by `bytes` in chunks of
+/// [`INPUT_CHUNK`].
+fn input_stdin(selector: u32, bytes: &[u8]) -> SP1Stdin {
+    let mut stdin = SP1Stdin::new();
+    stdin.write_slice(&selector.to_le_bytes());
+    stdin.write_slice(&(bytes.len() as u32).to_be_bytes());
+    for chunk in bytes.chunks(INPUT_CHUNK) {
+        stdin.write_slice(chunk);
+    }
+
+    stdin
+}
+
 pub struct Sp1 {
     prover: Box<dyn Prover<CpuProverComponents>>,
+    output_callback: Option<OutputCallback>,
+    reexecute_failed_proofs: bool,
+}
+
+impl Sp1 {
+    /// Streams guest stdout and stderr to `callback` while executing, in
+    /// addition to capturing them in the execution report.
+    pub fn with_output_callback(
+        mut self,
+        callback: impl Fn(OutputStream, &[u8]) + Send + Sync + 'static,
+    ) -> Self {
+        self.output_callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Stops re-executing the guest when proving fails. The guest is otherwise
+    /// re-executed to report guest panics as [`Error::GuestPanicked`] - the
+    /// prover does not run the hooks panics are reported through - at the cost
+    /// of doubling the time spent on failing proofs.
+    pub fn without_reexecution_on_prove_failure(mut self) -> Self {
+        self.reexecute_failed_proofs = false;
+        self
+    }
 }
 
 impl Default for Sp1 {
     fn default() -> Self {
         Self {
             prover: Box::new(ProverClient::from_env()),
+            output_callback: None,
+            reexecute_failed_proofs: true,
         }
     }
 }
@@ -29,11 +84,28 @@ impl Zkvm for Sp1 {
     type ProvingReport = Sp1ProvingReport;
 }
 
-pub struct Sp1ExecutionReport(sp1_sdk::ExecutionReport);
+pub struct Sp1ExecutionReport {
+    report: sp1_sdk::ExecutionReport,
+    logs: Vec<LogRecord>,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+}
 
 impl ExecutionReport for Sp1ExecutionReport {
     fn cycles(&self) -> u64 {
-        self.0.total_instruction_count()
+        self.report.total_instruction_count()
+    }
+
+    fn logs(&self) -> &[LogRecord] {
+        &self.logs
+    }
+
+    fn stdout(&self) -> &[u8] {
+        &self.stdout
+    }
+
+    fn stderr(&self) -> &[u8] {
+        &self.stderr
     }
 }
 
@@ -49,31 +121,106 @@ impl Proof for Sp1Proof {
     }
 }
 
-pub struct Sp1Program<In, Out, TIo: Io<In> + Io<Out>> {
+pub struct Sp1Program<In, Out, InIo: Io<In>, OutIo: Io<Out>> {
     elf: Vec<u8>,
-    io: TIo,
+    selector: u32,
+    committed_selector: Option<u32>,
+    input_io: InIo,
+    output_io: OutIo,
     pk: SP1ProvingKey,
     vk: SP1VerifyingKey,
 
     _phantom: PhantomData<(In, Out)>,
 }
 
-impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> Sp1Program<TInput, TOutput, TIo> {
-    pub fn new(vm: &Sp1, elf: &[u8], io: TIo) -> Self {
+impl<TInput, TOutput, InIo: Io<TInput>, OutIo: Io<TOutput>>
+    Sp1Program<TInput, TOutput, InIo, OutIo>
+{
+    /// Program running the guest function with `selector` out of the ones
+    /// exposed by `elf`. Guests exposing several functions commit the
+    /// selector after the output, as told by `commits_selector`.
+    pub fn new(
+        vm: &Sp1,
+        elf: &[u8],
+        selector: u32,
+        commits_selector: bool,
+        input_io: InIo,
+        output_io: OutIo,
+    ) -> Self {
         let (pk, vk) = vm.prover.setup(elf);
 
         Self {
             elf: elf.to_vec(),
-            io,
+            selector,
+            committed_selector: commits_selector.then_some(selector),
+            input_io,
+            output_io,
             pk,
             vk,
             _phantom: PhantomData,
         }
     }
+
+    fn run(
+        &self,
+        zkvm: &Sp1,
+        stdin: &SP1Stdin,
+    ) -> Result<(SP1PublicValues, Sp1ExecutionReport), Error> {
+        let logs = LogCollector::default();
+        let panics = PanicCollector::default();
+        let stdout = OutputCapture::new(OutputStream::Stdout, zkvm.output_callback.clone());
+        let stderr = OutputCapture::new(OutputStream::Stderr, zkvm.output_callback.clone());
+        let (mut stdout_writer, mut stderr_writer) = (stdout.clone(), stderr.clone());
+        let context = {
+            let logs = logs.clone();
+            let panics = panics.clone();
+
+            SP1Context::builder()
+                .hook(
+                    LOG_FD,
+                    hook_fn(move |_, record| {
+                        logs.push(record);
+                        vec![]
+                    }),
+                )
+                .hook(
+                    PANIC_FD,
+                    hook_fn(move |env, report| {
+                        // The guest cannot read the cycle counter, so it is
+                        // taken from the executor at the time of the report.
+                        if let Some(panic) = GuestPanic::decode(report) {
+                            panics.set(GuestPanic {
+                                cycles: env.runtime.state.global_clk,
+                                ..panic
+                            });
+                        }
+                        vec![]
+                    }),
+                )
+                .stdout(&mut stdout_writer)
+                .stderr(&mut stderr_writer)
+                .build()
+        };
+
+        let (values, _, report) = zkvm
+            .prover
+            .inner()
+            .execute(&self.elf, stdin, context)
+            .map_err(|err| panics.into_error(err))?;
+
+        let report = Sp1ExecutionReport {
+            report,
+            logs: logs.take(),
+            stdout: stdout.take(),
+            stderr: stderr.take(),
+        };
+
+        Ok((values, report))
+    }
 }
 
-impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> GuestProgram<Sp1>
-    for Sp1Program<TInput, TOutput, TIo>
+impl<TInput, TOutput, InIo: Io<TInput>, OutIo: Io<TOutput>> GuestProgram<Sp1>
+    for Sp1Program<TInput, TOutput, InIo, OutIo>
 {
     type Input = TInput;
     type Output = TOutput;
@@ -81,17 +228,17 @@ impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> GuestProgram<Sp1>
     fn execute(
         &self,
         zkvm: &Sp1,
-        input: Self::Input,
-    ) -> Result<(Self::Output, Sp1ExecutionReport), ()> {
-        let bytes = self.io.serialize(input).unwrap();
-        let mut stdin = SP1Stdin::new();
-
-        stdin.write_slice(&bytes);
+        input: &Self::Input,
+    ) -> Result<(Self::Output, Sp1ExecutionReport), Error> {
+        let bytes = self.input_io.serialize(input).map_err(Error::serialize)?;
+        let stdin = input_stdin(self.selector, &bytes);
 
-        let (values, report) = zkvm.prover.execute(&self.elf, &stdin).unwrap();
+        let (values, report) = self.run(zkvm, &stdin)?;
 
-        let output = self.io.deserialize(values.as_slice()).unwrap();
-        let report = Sp1ExecutionReport(report);
+        let output = self
+            .output_io
+            .deserialize(strip_selector(self.committed_selector, values.as_slice())?)
+            .map_err(Error::deserialize)?;
 
         Ok((output, report))
     }
@@ -99,26 +246,44 @@ impl<TInput, TOutput, TIo: Io<TInput> + Io<TOutput>> GuestProgram<Sp1>
     fn prove(
         &self,
         zkvm: &Sp1,
-        input: Self::Input,
-    ) -> Result<(Self::Output, Sp1Proof, Sp1ProvingReport), ()> {
-        let bytes = self.io.serialize(input).unwrap();
-        let mut stdin = SP1Stdin::new();
-        stdin.write_slice(&bytes);
+        input: &Self::Input,
+    ) -> Result<(Self::Output, Sp1Proof, Sp1ProvingReport), Error> {
+        let bytes = self.input_io.serialize(input).map_err(Error::serialize)?;
+        let stdin = input_stdin(self.selector, &bytes);
 
         let proof = zkvm
             .prover
             .prove(&self.pk, &stdin, sp1_sdk::SP1ProofMode::Core)
-            .unwrap();
+            .map_err(|err| {
+                if !zkvm.reexecute_failed_proofs {
+                    return Error::backend(err);
+                }
+
+                match self.run(zkvm, &stdin) {
+                    Err(panic @ Error::GuestPanicked { .. }) => panic,
+                    _ => Error::backend(err),
+                }
+            })?;
 
-        let output = self.io.deserialize(proof.public_values.as_slice()).unwrap();
+        let output = self
+            .output_io
+            .deserialize(strip_selector(
+                self.committed_selector,
+                proof.public_values.as_slice(),
+            )?)
+            .map_err(Error::deserialize)?;
 
         Ok((output, Sp1Proof(proof), Sp1ProvingReport {}))
     }
 
-    fn verify(&self, zkvm: &Sp1, proof: &Sp1Proof) -> Result<Self::Output, ()> {
-        zkvm.prover.verify(&proof.0, &self.vk).map_err(|_| ())?;
+    fn verify(&self, zkvm: &Sp1, proof: &Sp1Proof) -> Result<Self::Output, Error> {
+        zkvm.prover
+            .verify(&proof.0, &self.vk)
+            .map_err(Error::verification)?;
 
-        let values = proof.0.public_values.as_slice();
-        self.io.deserialize(values).map_err(|_| ())
+        let values = strip_selector(self.committed_selector, proof.0.public_values.as_slice())?;
+        self.output_io
+            .deserialize(values)
+            .map_err(Error::deserialize)
     }
 }
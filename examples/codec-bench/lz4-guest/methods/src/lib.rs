@@ -0,0 +1,25 @@
+use ssz::{ContiguousList, Ssz};
+use typenum::U1048576;
+use univm_io::{compressed::Compressed, ssz::SszIo};
+
+#[derive(Debug, Ssz)]
+pub struct Input {
+    pub values: ContiguousList<u64, U1048576>,
+}
+
+#[derive(Debug, Ssz)]
+pub struct Output {
+    pub sum: u64,
+}
+
+/// Same computation as `sum_ssz`, with the input LZ4-compressed on its way to
+/// the guest.
+#[univm_platform::function(input = Compressed<SszIo>, output = SszIo)]
+pub fn sum_lz4(input: Input) -> Output {
+    Output {
+        sum: input
+            .values
+            .iter()
+            .fold(0, |sum, value| sum.wrapping_add(*value)),
+    }
+}
@@ -0,0 +1,5 @@
+#![no_main]
+
+use lz4_guest_methods_lib::sum_lz4;
+
+univm_platform::entrypoint!(sum_lz4);
@@ -0,0 +1,3 @@
+include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+
+pub use lz4_guest_methods_lib::{Input, Output};
@@ -0,0 +1,24 @@
+use rkyv::{Archive, Deserialize, Serialize};
+use univm_io::rkyv::RkyvIo;
+
+#[derive(Debug, Archive, Serialize, Deserialize)]
+pub struct Input {
+    pub values: Vec<u64>,
+}
+
+#[derive(Debug, Archive, Serialize, Deserialize)]
+pub struct Output {
+    pub sum: u64,
+}
+
+/// Same computation as `sum_ssz`, but reads the values in place from the
+/// input buffer instead of deserializing them first.
+#[univm_platform::function(RkyvIo, owned = Input)]
+pub fn sum_rkyv(input: &ArchivedInput) -> Output {
+    Output {
+        sum: input
+            .values
+            .iter()
+            .fold(0, |sum, value| sum.wrapping_add(value.to_native())),
+    }
+}
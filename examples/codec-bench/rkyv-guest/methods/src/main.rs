@@ -0,0 +1,5 @@
+#![no_main]
+
+use rkyv_guest_methods_lib::sum_rkyv;
+
+univm_platform::entrypoint!(sum_rkyv);
@@ -0,0 +1,100 @@
+//! Compares guest cycles spent on the same computation over a large input,
+//! decoded with `SszIo`, accessed in place with `RkyvIo`, and decompressed
+//! from `Compressed<SszIo>`, along with the size of the input sent to the
+//! prover.
+
+use lz4_guest::{SumLz4, SumLz4Risc0, SumLz4Sp1};
+use rkyv_guest::{SumRkyv, SumRkyvRisc0, SumRkyvSp1};
+use ssz_guest::{SumSsz, SumSszRisc0, SumSszSp1};
+use univm_interface::{
+    ExecutionReport as _, GuestProgram, GuestProgramBuilder, Zkvm, ZkvmMethods as _,
+};
+use univm_io::{Io, compressed::Compressed, rkyv::RkyvIo, ssz::SszIo};
+use univm_risc0::Risc0;
+use univm_sp1::Sp1;
+
+/// 2 MiB worth of `u64` values.
+const VALUES: u64 = 1 << 18;
+
+struct Inputs {
+    ssz: ssz_guest::Input,
+    rkyv: rkyv_guest::Input,
+    lz4: lz4_guest::Input,
+}
+
+impl Inputs {
+    fn new() -> Self {
+        // Values repeat, as fields of real-world inputs tend to.
+        let values = (0..VALUES).map(|value| value % 1000).collect::<Vec<_>>();
+
+        Self {
+            ssz: ssz_guest::Input {
+                values: values.clone().try_into().unwrap(),
+            },
+            rkyv: rkyv_guest::Input {
+                values: values.clone(),
+            },
+            lz4: lz4_guest::Input {
+                values: values.try_into().unwrap(),
+            },
+        }
+    }
+}
+
+fn cycles<V: Zkvm, P: GuestProgram<V>>(vm: &V, program: &P, input: &P::Input) -> u64 {
+    let (_, report) = program.execute(vm, input).unwrap();
+
+    report.cycles()
+}
+
+fn print_row(vm: &str, codec: &str, bytes: usize, cycles: u64) {
+    println!("{vm:<8}{codec:<20}{bytes:>14}{cycles:>16}");
+}
+
+fn bench<V: Zkvm>(
+    vm: &V,
+    inputs: &Inputs,
+    ssz: impl GuestProgram<V, Input = ssz_guest::Input>,
+    rkyv: impl GuestProgram<V, Input = rkyv_guest::Input>,
+    lz4: impl GuestProgram<V, Input = lz4_guest::Input>,
+) {
+    print_row(
+        vm.name(),
+        "SszIo",
+        SszIo.serialize(&inputs.ssz).unwrap().len(),
+        cycles(vm, &ssz, &inputs.ssz),
+    );
+    print_row(
+        vm.name(),
+        "RkyvIo",
+        RkyvIo.serialize(&inputs.rkyv).unwrap().len(),
+        cycles(vm, &rkyv, &inputs.rkyv),
+    );
+    print_row(
+        vm.name(),
+        "Compressed<SszIo>",
+        Compressed(SszIo).serialize(&inputs.lz4).unwrap().len(),
+        cycles(vm, &lz4, &inputs.lz4),
+    );
+}
+
+fn main() {
+    let inputs = Inputs::new();
+
+    println!(
+        "{:<8}{:<20}{:>14}{:>16}",
+        "zkvm", "codec", "input bytes", "cycles"
+    );
+
+    let vm = Risc0::default();
+    let ssz: SumSszRisc0 = SumSsz::init(&vm);
+    let rkyv: SumRkyvRisc0 = SumRkyv::init(&vm);
+    let lz4: SumLz4Risc0 = SumLz4::init(&vm);
+    bench(&vm, &inputs, ssz, rkyv, lz4);
+
+    let vm = Sp1::default();
+    let ssz: SumSszSp1 = SumSsz::init(&vm);
+    let rkyv: SumRkyvSp1 = SumRkyv::init(&vm);
+    let lz4: SumLz4Sp1 = SumLz4::init(&vm);
+    bench(&vm, &inputs, ssz, rkyv, lz4);
+}
@@ -0,0 +1,23 @@
+use ssz::{ContiguousList, Ssz};
+use typenum::U1048576;
+use univm_io::ssz::SszIo;
+
+#[derive(Debug, Ssz)]
+pub struct Input {
+    pub values: ContiguousList<u64, U1048576>,
+}
+
+#[derive(Debug, Ssz)]
+pub struct Output {
+    pub sum: u64,
+}
+
+#[univm_platform::function(SszIo)]
+pub fn sum_ssz(input: Input) -> Output {
+    Output {
+        sum: input
+            .values
+            .iter()
+            .fold(0, |sum, value| sum.wrapping_add(*value)),
+    }
+}
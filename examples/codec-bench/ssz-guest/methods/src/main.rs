@@ -0,0 +1,5 @@
+#![no_main]
+
+use ssz_guest_methods_lib::sum_ssz;
+
+univm_platform::entrypoint!(sum_ssz);
@@ -0,0 +1,55 @@
+//! Guest functions built without `std`.
+//!
+//! Without `std` there is no panic hook, so a panicking guest is not reported
+//! to the host as `Error::GuestPanicked` - the panic handler of the zkvm
+//! runtime aborts the guest, and the host gets a backend error instead. Logs
+//! are still collected.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+use ssz::Ssz;
+use univm_io::{postcard::PostcardIo, ssz::SszIo};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Input {
+    pub values: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Output {
+    pub sum: u64,
+    pub max: u32,
+}
+
+#[derive(Debug, Ssz)]
+pub struct Pair {
+    pub a: u64,
+    pub b: u64,
+}
+
+#[derive(Debug, Ssz)]
+pub struct Product {
+    pub value: u64,
+}
+
+#[univm_platform::function(PostcardIo)]
+pub fn stats(input: Input) -> Output {
+    univm_platform::info!("summarizing {} values", input.values.len());
+
+    Output {
+        sum: input.values.iter().map(|&value| u64::from(value)).sum(),
+        max: input.values.iter().copied().max().unwrap_or_default(),
+    }
+}
+
+#[univm_platform::function(SszIo)]
+pub fn multiply(input: Pair) -> Product {
+    Product {
+        value: input.a.checked_mul(input.b).unwrap(),
+    }
+}
@@ -0,0 +1,6 @@
+#![no_std]
+#![no_main]
+
+use no_std_guest_methods_lib::{multiply, stats};
+
+univm_platform::entrypoint!(stats, multiply);
@@ -0,0 +1,30 @@
+use no_std_guest::{
+    Input, Multiply, MultiplyRisc0, MultiplySp1, Pair, Stats, StatsRisc0, StatsSp1,
+};
+use univm_interface::{GuestProgram, GuestProgramBuilder};
+use univm_risc0::Risc0;
+use univm_sp1::Sp1;
+
+fn main() {
+    let input = Input {
+        values: (1..=100).collect(),
+    };
+
+    let vm = Risc0::default();
+    let program: StatsRisc0 = Stats::init(&vm);
+    let (output, _) = program.execute(&vm, &input).unwrap();
+    println!("risc0: {output:?}");
+
+    let program: MultiplyRisc0 = Multiply::init(&vm);
+    let (output, _) = program.execute(&vm, &Pair { a: 6, b: 7 }).unwrap();
+    println!("risc0: {output:?}");
+
+    let vm = Sp1::default();
+    let program: StatsSp1 = Stats::init(&vm);
+    let (output, _) = program.execute(&vm, &input).unwrap();
+    println!("sp1: {output:?}");
+
+    let program: MultiplySp1 = Multiply::init(&vm);
+    let (output, _) = program.execute(&vm, &Pair { a: 6, b: 7 }).unwrap();
+    println!("sp1: {output:?}");
+}
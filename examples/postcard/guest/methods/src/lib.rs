@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use univm_io::postcard::PostcardIo;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Input {
+    pub a: u64,
+    pub b: u64,
+}
+
+/// Commits the input as decoded by the guest, for the host to compare.
+#[univm_platform::function(PostcardIo)]
+pub fn roundtrip(input: Input) -> Input {
+    input
+}
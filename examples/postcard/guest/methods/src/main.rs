@@ -0,0 +1,5 @@
+#![no_main]
+
+use postcard_guest_methods_lib::roundtrip;
+
+univm_platform::entrypoint!(roundtrip);
@@ -0,0 +1,3 @@
+include!(concat!(env!("OUT_DIR"), "/methods.rs"));
+
+pub use postcard_guest_methods_lib::Input;
@@ -0,0 +1,37 @@
+//! Checks that `PostcardIo` values decoded by the guest round-trip to the
+//! same values on the host, on every backend.
+
+use postcard_guest::{Input, Roundtrip, RoundtripRisc0, RoundtripSp1};
+use univm_interface::{GuestProgram, GuestProgramBuilder, Zkvm};
+use univm_risc0::Risc0;
+use univm_sp1::Sp1;
+
+fn inputs() -> Vec<Input> {
+    vec![
+        Input { a: 0, b: 0 },
+        Input { a: 1, b: 300 },
+        Input {
+            a: u64::MAX,
+            b: 1 << 35,
+        },
+    ]
+}
+
+fn check<V: Zkvm>(vm: &V, program: impl GuestProgram<V, Input = Input, Output = Input>) {
+    for input in inputs() {
+        let (output, _) = program.execute(vm, &input).unwrap();
+        assert_eq!(output, input);
+    }
+}
+
+fn main() {
+    let vm = Risc0::default();
+    let program: RoundtripRisc0 = Roundtrip::init(&vm);
+    check(&vm, program);
+
+    let vm = Sp1::default();
+    let program: RoundtripSp1 = Roundtrip::init(&vm);
+    check(&vm, program);
+
+    println!("inputs round-trip identically on the host and on all backends");
+}
@@ -12,11 +12,23 @@ pub struct Output {
     pub sum: u64,
 }
 
+#[derive(Debug, Ssz)]
+pub struct Difference {
+    pub value: u64,
+}
+
 #[univm_platform::function(SszIo)]
 pub fn state_transition(input: Input) -> Output {
-    println!("Hello, world!");
+    univm_platform::info!("adding {} and {}", input.a, input.b);
 
     Output {
         sum: input.a.checked_add(input.b).unwrap(),
     }
 }
+
+#[univm_platform::function(SszIo)]
+pub fn subtract(input: Input) -> Difference {
+    Difference {
+        value: input.a.checked_sub(input.b).unwrap(),
+    }
+}
@@ -1,5 +1,5 @@
 #![no_main]
 
-use zkvm_guest_methods_lib::state_transition;
+use zkvm_guest_methods_lib::{state_transition, subtract};
 
-univm_platform::entrypoint!(state_transition);
+univm_platform::entrypoint!(state_transition, subtract);